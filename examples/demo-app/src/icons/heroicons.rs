@@ -1,5 +1,4 @@
 /// Auto-generated by dioxus-iconify - DO NOT EDIT
-/// Generated: 2025-12-07T18:42:47.101876487+00:00
 /// Collection: heroicons
 /// This is a partial import from Iconify
 /// Browse icons: <https://icon-sets.iconify.design/heroicons/>
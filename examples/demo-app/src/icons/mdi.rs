@@ -1,5 +1,4 @@
 /// Auto-generated by dioxus-iconify - DO NOT EDIT
-/// Generated: 2025-12-07T18:42:47.102110686+00:00
 /// Collection: mdi
 /// This is a partial import from Iconify
 /// Browse icons: <https://icon-sets.iconify.design/mdi/>
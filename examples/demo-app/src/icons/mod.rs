@@ -16,6 +16,10 @@ pub fn Icon(
     /// Optional size to set both width and height
     #[props(default, into)]
     size: String,
+    /// Give the IDs of the body a per-instance suffix, for icons with gradients,
+    /// clip paths or masks rendered several times on a page
+    #[props(default)]
+    unique_ids: bool,
     /// Additional attributes to extend the svg element
     #[props(extends = SvgAttributes)]
     attributes: Vec<Attribute>,
@@ -26,17 +30,63 @@ pub fn Icon(
         (size.as_str(), size.as_str())
     };
 
+    let body = if unique_ids {
+        // IDs are prefixed with the icon name on import ("mdi:home" → "mdi_home_"), the
+        // scope of the instance is the same when rendered on the server and hydrated
+        let prefix = format!("{}_", data.name.replace(':', "_"));
+        scope_ids(data.body, &prefix, dioxus::core::current_scope_id().0)
+    } else {
+        data.body.to_string()
+    };
+
     rsx! {
         svg {
             view_box: "{data.view_box}",
             width: "{width}",
             height: "{height}",
-            dangerous_inner_html: "{data.body}",
+            dangerous_inner_html: "{body}",
             ..attributes,
         }
     }
 }
 
+/// Insert the scope of an instance after the prefix of the IDs of a body, and of the
+/// references to them ("mdi_home_a" → "mdi_home_3_a")
+fn scope_ids(body: &str, prefix: &str, scope: usize) -> String {
+    let mut scoped = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find(prefix) {
+        let (before, after) = rest.split_at(start + prefix.len());
+        scoped.push_str(before);
+        if is_id_position(&scoped[..scoped.len() - prefix.len()]) {
+            scoped.push_str(&format!("{}_", scope));
+        }
+        rest = after;
+    }
+    scoped.push_str(rest);
+    scoped
+}
+
+/// Whether an ID starts after `before`: in an `id` attribute, an ARIA ID list, or a `#id`
+/// reference (`href`, `url(#id)`, stylesheet selectors)
+fn is_id_position(before: &str) -> bool {
+    if before.ends_with('#') {
+        return true;
+    }
+    let Some(start) = before.rfind("=\"") else {
+        return false;
+    };
+    let value = &before[start + 2..];
+    let name = before[..start].rsplit(' ').next().unwrap_or_default();
+    match name {
+        "id" => value.is_empty(),
+        "aria-labelledby" | "aria-describedby" => {
+            !value.contains('"') && (value.is_empty() || value.ends_with(' '))
+        }
+        _ => false,
+    }
+}
+
 pub mod app;
 pub mod heroicons;
 pub mod mdi;
@@ -1,19 +1,188 @@
 use anyhow::{Context, Result, anyhow};
+use futures_util::stream::{self, StreamExt};
+use reqwest::{StatusCode, header};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
-const API_BASE_URL: &str = "https://api.iconify.design";
+use crate::cache::{CachedResponse, HttpCache};
+use crate::iconify_json::IconifyJson;
+use crate::monochrome::Monochrome;
+use crate::optimize::Preset;
+
+/// Default (public) Iconify API
+pub const API_BASE_URL: &str = "https://api.iconify.design";
+
+/// Maximum length of the `icons` query parameter, to stay under URL length limits
+/// (same limit as the official Iconify clients)
+const MAX_ICONS_PARAM_LENGTH: usize = 480;
+
+/// Default timeout of each request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of concurrent requests to the Iconify API
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Default width and height of icons without dimensions
+pub const DEFAULT_ICON_SIZE: f64 = 24.0;
 
 /// Icon data returned from the Iconify API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IconifyIcon {
     pub body: String,
+    /// Left position of the viewBox
     #[serde(default)]
-    pub width: Option<u32>,
+    pub left: Option<f64>,
+    /// Top position of the viewBox
     #[serde(default)]
-    pub height: Option<u32>,
+    pub top: Option<f64>,
+    #[serde(default)]
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub height: Option<f64>,
+    /// Explicit viewBox, when it isn't `left top width height`
+    /// (e.g., SVG files, or icons resized by the manifest options)
     #[serde(default, rename = "viewBox")]
     pub view_box: Option<String>,
+    /// Where the icon comes from (not part of the API data)
+    #[serde(skip)]
+    pub origin: IconOrigin,
+}
+
+/// Where a vendored icon comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "origin", rename_all = "lowercase")]
+pub enum IconOrigin {
+    /// Fetched from the Iconify API
+    Iconify {
+        /// `lastModified` timestamp of the collection, as reported by the API
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_modified: Option<u64>,
+    },
+    /// Imported from a custom IconifyJSON file (e.g., a company icon set)
+    #[serde(rename = "iconset")]
+    IconSet {
+        path: PathBuf,
+        /// `lastModified` timestamp of the collection, if the file has one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_modified: Option<u64>,
+        /// Colour normalization applied to the body
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        monochrome: Option<Monochrome>,
+    },
+    /// Imported from a local SVG file (or one `<symbol>` of a sprite sheet)
+    Svg {
+        path: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        symbol: Option<String>,
+        /// Optimization preset applied to the body
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        optimize: Option<Preset>,
+        /// Colour normalization applied to the body
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        monochrome: Option<Monochrome>,
+    },
+    /// Imported from an SVG file of an archive (npm tarball or zip)
+    Archive {
+        path: PathBuf,
+        /// Path of the SVG file in the archive
+        entry: String,
+        /// SHA-256 of the archive, formatted as "sha256:<hex>"
+        archive_hash: String,
+        /// Optimization preset applied to the body
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        optimize: Option<Preset>,
+        /// Colour normalization applied to the body
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        monochrome: Option<Monochrome>,
+    },
+}
+
+impl IconOrigin {
+    /// The optimization preset applied to a local icon, if any
+    pub fn optimize(&self) -> Option<Preset> {
+        match self {
+            IconOrigin::Svg { optimize, .. } | IconOrigin::Archive { optimize, .. } => *optimize,
+            IconOrigin::Iconify { .. } | IconOrigin::IconSet { .. } => None,
+        }
+    }
+
+    /// The local file an icon was imported from, if any
+    pub fn path_mut(&mut self) -> Option<&mut PathBuf> {
+        match self {
+            IconOrigin::IconSet { path, .. }
+            | IconOrigin::Svg { path, .. }
+            | IconOrigin::Archive { path, .. } => Some(path),
+            IconOrigin::Iconify { .. } => None,
+        }
+    }
+
+    /// The colour normalization applied to a local icon, if any
+    pub fn monochrome(&self) -> Option<Monochrome> {
+        match self {
+            IconOrigin::IconSet { monochrome, .. }
+            | IconOrigin::Svg { monochrome, .. }
+            | IconOrigin::Archive { monochrome, .. } => *monochrome,
+            IconOrigin::Iconify { .. } => None,
+        }
+    }
+}
+
+impl IconifyIcon {
+    /// The viewBox to emit: the explicit one, or `left top width height`
+    pub fn effective_view_box(&self) -> String {
+        self.view_box.clone().unwrap_or_else(|| {
+            format!(
+                "{} {} {} {}",
+                self.left.unwrap_or(0.0),
+                self.top.unwrap_or(0.0),
+                self.width.unwrap_or(DEFAULT_ICON_SIZE),
+                self.height.unwrap_or(DEFAULT_ICON_SIZE)
+            )
+        })
+    }
+}
+
+impl Default for IconOrigin {
+    fn default() -> Self {
+        Self::Iconify {
+            last_modified: None,
+        }
+    }
+}
+
+/// Error returned when an icon doesn't exist (anymore) in a collection
+#[derive(Debug)]
+pub struct IconNotFound {
+    pub collection: String,
+    pub icon_name: String,
+}
+
+impl std::fmt::Display for IconNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Icon '{}' not found in collection '{}'",
+            self.icon_name, self.collection
+        )
+    }
+}
+
+impl std::error::Error for IconNotFound {}
+
+/// Icons fetched from a collection
+#[derive(Debug, Clone, Default)]
+pub struct FetchedIcons {
+    /// Fetched icons keyed by icon name
+    pub icons: HashMap<String, IconifyIcon>,
+    /// Requested icons that don't exist in the collection
+    /// (the API `not_found` list, or icons missing from the response)
+    pub not_found: Vec<String>,
+    /// Where the icons were fetched from (API host or local file)
+    pub source: String,
 }
 
 /// Wrapper for the collection API response
@@ -31,108 +200,482 @@ struct IconifyCollectionResponse {
 
 /// Collection information from Iconify API
 /// Based on IconifyInfo: https://iconify.design/docs/types/iconify-info.html
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct IconifyCollectionInfo {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub author: Option<IconifyAuthor>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub license: Option<IconifyLicense>,
-    #[serde(default)]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_info_height",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub height: Option<u32>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub palette: Option<bool>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub total: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Collection height is either a number or a list of numbers (first one is used)
+fn deserialize_info_height<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Height {
+        Single(u32),
+        Multiple(Vec<u32>),
+    }
+
+    Ok(match Option::<Height>::deserialize(deserializer)? {
+        Some(Height::Single(height)) => Some(height),
+        Some(Height::Multiple(heights)) => heights.first().copied(),
+        None => None,
+    })
 }
 
 /// Author information in collection metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum IconifyAuthor {
     Simple(String),
     Detailed {
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         name: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         url: Option<String>,
     },
 }
 
 /// License information in collection metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum IconifyLicense {
     Simple(String),
     Detailed {
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         title: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         spdx: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         url: Option<String>,
     },
 }
 
-/// API response structure for icon data
-#[derive(Debug, Deserialize)]
-struct IconifyApiResponse {
-    // prefix: String,
-    icons: HashMap<String, IconifyIcon>,
+/// Filters for the Iconify search API
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Restrict results to a collection prefix (e.g., "mdi")
+    pub prefix: Option<String>,
+    /// Maximum number of results
+    pub limit: Option<u32>,
+    /// Restrict results to a collection category (e.g., "General")
+    pub category: Option<String>,
+    /// Restrict results to colored (true) or monotone (false) icons
+    pub palette: Option<bool>,
+}
+
+/// Search results from the Iconify API
+/// Based on APIv2SearchResponse: https://iconify.design/docs/api/search.html
+#[derive(Debug, Clone, Deserialize)]
+pub struct IconifySearchResponse {
+    /// Icon identifiers in "collection:icon-name" format
+    pub icons: Vec<String>,
+    /// Number of results (equal to `limit` when more icons are available)
+    #[serde(default)]
+    pub total: u32,
     #[serde(default)]
-    width: Option<u32>,
+    pub limit: u32,
+    /// Info of the collections the icons belong to
     #[serde(default)]
-    height: Option<u32>,
+    pub collections: HashMap<String, IconifyCollectionInfo>,
 }
 
 /// Iconify API client
 pub struct IconifyClient {
     client: reqwest::Client,
-    base_url: String,
+    /// API hosts, tried in order until one answers
+    base_urls: Vec<String>,
+    /// Index of the last host that answered (tried first by the next requests)
+    active: AtomicUsize,
+    cache: Option<HttpCache>,
+    offline: bool,
+    /// Timeout of each request
+    timeout: Duration,
+    retry_policy: RetryPolicy,
+}
+
+/// How failed requests (connection errors, timeouts, 5xx and 429) are retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt (each retry tries every API host)
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each next retry
+    pub base_delay: Duration,
+    /// Maximum delay between retries (also caps `Retry-After`)
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the retry following `round` (0-based): exponential backoff,
+    /// with a random jitter between 50% and 100% of the delay
+    fn backoff(&self, round: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(round))
+            .min(self.max_delay);
+
+        let jitter = RandomState::new().build_hasher().finish() % 1000;
+        delay.mul_f64(0.5 + jitter as f64 / 2000.0)
+    }
+}
+
+/// Error returned when the API can't be reached, after every retry and API host
+/// (connection errors, timeouts, 5xx and 429)
+#[derive(Debug)]
+pub struct NetworkError {
+    /// Last requested URL
+    pub url: String,
+    /// Last failure
+    pub message: String,
+    pub attempts: u32,
+}
+
+impl std::fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Network problem with {} ({}) after {} attempt(s)",
+            self.url, self.message, self.attempts
+        )
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+/// Response of the Iconify API (possibly served from the cache)
+struct ApiResponse<'a> {
+    status: StatusCode,
+    body: String,
+    /// API host that answered
+    base_url: &'a str,
+}
+
+impl ApiResponse<'_> {
+    /// Turn an unsuccessful response into an error
+    fn error_for_status(self) -> Result<Self> {
+        if !self.status.is_success() {
+            return Err(anyhow!(
+                "API request failed with status {}: {}",
+                self.status,
+                self.body
+            ));
+        }
+
+        Ok(self)
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
 }
 
 impl IconifyClient {
     /// Create a new Iconify API client
     pub fn new() -> Result<Self> {
+        Self::with_base_urls(&[API_BASE_URL.to_string()])
+    }
+
+    /// Create a new Iconify API client for other API hosts (e.g., mirrors or a local server)
+    ///
+    /// Hosts are tried in order: the next one is used when a host is unreachable
+    /// or answers with a server error (5xx).
+    pub fn with_base_urls(base_urls: &[String]) -> Result<Self> {
+        if base_urls.is_empty() {
+            return Err(anyhow!("At least one Iconify API URL is required"));
+        }
+
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
             .build()
             .context("Failed to create HTTP client")?;
 
         Ok(Self {
             client,
-            base_url: API_BASE_URL.to_string(),
+            base_urls: base_urls
+                .iter()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .collect(),
+            active: AtomicUsize::new(0),
+            cache: None,
+            offline: false,
+            timeout: DEFAULT_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
         })
     }
 
-    /// Fetch collection information from the Iconify API
-    pub async fn fetch_collection_info(&self, collection: &str) -> Result<IconifyCollectionInfo> {
-        let url = format!(
-            "{}/collection?prefix={}&info=true",
-            self.base_url, collection
-        );
+    /// Set the timeout of each request (default: 30 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
 
-        let response = self.client.get(&url).send().await.context(format!(
-            "Failed to fetch collection info for '{}'",
-            collection
-        ))?;
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "API request failed with status {}: {}",
+    /// Cache the responses in `cache`, and revalidate them with the API
+    /// When `offline` is set, responses are only served from the cache
+    pub fn with_cache(mut self, cache: HttpCache, offline: bool) -> Self {
+        self.cache = Some(cache);
+        self.offline = offline;
+        self
+    }
+
+    /// Send a GET request, failing over to the next API host on connection errors, timeouts,
+    /// 5xx and 429, then retrying every host with backoff (see `RetryPolicy`)
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<ApiResponse<'_>> {
+        if self.offline {
+            return self.get_cached(path, query);
+        }
+
+        let count = self.base_urls.len();
+        let mut round = 0;
+
+        loop {
+            let start = self.active.load(Ordering::Relaxed);
+            let is_last_round = round == self.retry_policy.max_retries;
+            // Longest `Retry-After` asked by the hosts in this round
+            let mut retry_after = None;
+
+            for attempt in 0..count {
+                let index = (start + attempt) % count;
+                let base_url = &self.base_urls[index];
+
+                let mut request = self
+                    .client
+                    .get(format!("{}{}", base_url, path))
+                    .query(query)
+                    .timeout(self.timeout)
+                    .build()
+                    .context("Failed to build request")?;
+                let url = request.url().to_string();
+
+                // Revalidate the cached response, if any
+                let cached = self.cache.as_ref().and_then(|cache| cache.get(&url));
+                if let Some(cached) = &cached {
+                    let headers = request.headers_mut();
+                    if let Some(etag) = cached.etag.as_deref().and_then(|v| v.parse().ok()) {
+                        headers.insert(header::IF_NONE_MATCH, etag);
+                    }
+                    if let Some(date) = cached.last_modified.as_deref().and_then(|v| v.parse().ok())
+                    {
+                        headers.insert(header::IF_MODIFIED_SINCE, date);
+                    }
+                }
+
+                let error = match self.client.execute(request).await {
+                    Ok(response)
+                        if response.status() == StatusCode::NOT_MODIFIED && cached.is_some() =>
+                    {
+                        self.active.store(index, Ordering::Relaxed);
+                        return Ok(ApiResponse {
+                            status: StatusCode::OK,
+                            body: cached.map(|cached| cached.body).unwrap_or_default(),
+                            base_url,
+                        });
+                    }
+                    Ok(response) if !is_retryable_status(response.status()) => {
+                        self.active.store(index, Ordering::Relaxed);
+                        return self.read_response(url, response, base_url).await;
+                    }
+                    Ok(response) => {
+                        if let Some(delay) = parse_retry_after(response.headers()) {
+                            retry_after = retry_after.max(Some(delay));
+                        }
+                        format!("status {}", response.status())
+                    }
+                    Err(err) => error_message(&err),
+                };
+
+                if attempt + 1 < count {
+                    eprintln!(
+                        "  ⚠ {} unavailable ({}), trying {}",
+                        base_url,
+                        error,
+                        self.base_urls[(index + 1) % count]
+                    );
+                } else if is_last_round {
+                    return Err(NetworkError {
+                        url,
+                        message: error,
+                        attempts: round + 1,
+                    }
+                    .into());
+                } else {
+                    let delay = match retry_after {
+                        Some(delay) => delay.min(self.retry_policy.max_delay),
+                        None => self.retry_policy.backoff(round),
+                    };
+                    eprintln!(
+                        "  ⚠ {} unavailable ({}), retrying in {:.1}s ({}/{})",
+                        base_url,
+                        error,
+                        delay.as_secs_f64(),
+                        round + 1,
+                        self.retry_policy.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+
+            round += 1;
+        }
+    }
+
+    /// Read a response, and cache it when successful
+    async fn read_response<'a>(
+        &self,
+        url: String,
+        response: reqwest::Response,
+        base_url: &'a str,
+    ) -> Result<ApiResponse<'a>> {
+        let status = response.status();
+        let header_value = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &header::HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header_value(header::ETAG);
+        let last_modified = header_value(header::LAST_MODIFIED);
+
+        let body = response
+            .text()
+            .await
+            .context(format!("Failed to read response from {}", base_url))?;
+
+        if let Some(cache) = &self.cache
+            && status == StatusCode::OK
+        {
+            let cached = CachedResponse {
+                url,
+                etag,
+                last_modified,
+                body,
+            };
+            if let Err(e) = cache.put(&cached) {
+                eprintln!("  ⚠ Failed to cache response: {:#}", e);
+            }
+            return Ok(ApiResponse {
                 status,
-                text
-            ));
+                body: cached.body,
+                base_url,
+            });
+        }
+
+        Ok(ApiResponse {
+            status,
+            body,
+            base_url,
+        })
+    }
+
+    /// Serve a GET request from the cache only (offline mode)
+    fn get_cached(&self, path: &str, query: &[(&str, String)]) -> Result<ApiResponse<'_>> {
+        let mut urls = Vec::new();
+        for base_url in &self.base_urls {
+            let request = self
+                .client
+                .get(format!("{}{}", base_url, path))
+                .query(query)
+                .build()
+                .context("Failed to build request")?;
+            let url = request.url().to_string();
+
+            if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(&url)) {
+                return Ok(ApiResponse {
+                    status: StatusCode::OK,
+                    body: cached.body,
+                    base_url,
+                });
+            }
+            urls.push(url);
+        }
+
+        Err(anyhow!(
+            "{} is not in the cache (offline mode)",
+            urls.join(", ")
+        ))
+    }
+
+    /// Search icons using the Iconify search API
+    pub async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<IconifySearchResponse> {
+        let mut params = vec![("query", query.to_string())];
+        if let Some(prefix) = &options.prefix {
+            params.push(("prefix", prefix.clone()));
+        }
+        if let Some(limit) = options.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(category) = &options.category {
+            params.push(("category", category.clone()));
         }
+        if let Some(palette) = options.palette {
+            params.push(("palette", palette.to_string()));
+        }
+
+        let response = self
+            .get("/search", &params)
+            .await
+            .context(format!("Failed to search icons for '{}'", query))?
+            .error_for_status()?;
+
+        response.json().context("Failed to parse search response")
+    }
+
+    /// Fetch collection information from the Iconify API
+    pub async fn fetch_collection_info(&self, collection: &str) -> Result<IconifyCollectionInfo> {
+        let params = [
+            ("prefix", collection.to_string()),
+            ("info", "true".to_string()),
+        ];
+
+        let response = self
+            .get("/collection", &params)
+            .await
+            .context(format!(
+                "Failed to fetch collection info for '{}'",
+                collection
+            ))?
+            .error_for_status()?;
 
         let response_wrapper: IconifyCollectionResponse = response
             .json()
-            .await
             .context("Failed to parse collection info response")?;
 
         // Extract the info field, or create a basic one from the wrapper
@@ -144,121 +687,138 @@ impl IconifyClient {
             category: None,
             palette: None,
             total: response_wrapper.total,
+            version: None,
         });
 
         Ok(collection_info)
     }
 
-    /// Fetch a single icon from the Iconify API
-    pub async fn fetch_icon(&self, collection: &str, icon_name: &str) -> Result<IconifyIcon> {
-        let url = format!("{}/{}.json?icons={}", self.base_url, collection, icon_name);
+    /// Fetch multiple icons from the same collection
+    ///
+    /// Icons are requested in batches (`?icons=a,b,c`), split to stay under URL length limits.
+    pub async fn fetch_icons(
+        &self,
+        collection: &str,
+        icon_names: &[String],
+    ) -> Result<FetchedIcons> {
+        let mut fetched = FetchedIcons::default();
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context(format!("Failed to fetch icon {}:{}", collection, icon_name))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "API request failed with status {}: {}",
-                status,
-                text
-            ));
+        for chunk in chunk_icon_names(icon_names) {
+            let chunk_fetched = self.fetch_icons_chunk(collection, &chunk).await?;
+            fetched.icons.extend(chunk_fetched.icons);
+            fetched.not_found.extend(chunk_fetched.not_found);
+            fetched.source = chunk_fetched.source;
         }
 
-        let api_response: IconifyApiResponse = response
-            .json()
-            .await
-            .context("Failed to parse API response")?;
-
-        let icon = api_response
-            .icons
-            .get(icon_name)
-            .ok_or_else(|| {
-                anyhow!(
-                    "Icon '{}' not found in collection '{}'",
-                    icon_name,
-                    collection
+        Ok(fetched)
+    }
+
+    /// Fetch icons from several collections concurrently (at most `MAX_CONCURRENT_REQUESTS`)
+    ///
+    /// Results are returned per collection, sorted by collection name.
+    pub async fn fetch_collections(
+        &self,
+        icons_by_collection: &BTreeMap<String, Vec<String>>,
+    ) -> Vec<(String, Result<FetchedIcons>)> {
+        let mut results: Vec<_> = stream::iter(icons_by_collection)
+            .map(|(collection, icon_names)| async move {
+                (
+                    collection.clone(),
+                    self.fetch_icons(collection, icon_names).await,
                 )
-            })?
-            .clone();
-
-        // Use icon-specific dimensions or fall back to collection defaults
-        let width = icon.width.or(api_response.width).unwrap_or(24);
-        let height = icon.height.or(api_response.height).unwrap_or(24);
-
-        // Generate viewBox if not provided
-        let view_box = icon
-            .view_box
-            .clone()
-            .unwrap_or_else(|| format!("0 0 {} {}", width, height));
-
-        Ok(IconifyIcon {
-            body: icon.body,
-            width: Some(width),
-            height: Some(height),
-            view_box: Some(view_box),
-        })
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .collect()
+            .await;
+
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        results
+    }
+
+    /// Fetch a batch of icons that fits in a single request
+    async fn fetch_icons_chunk(
+        &self,
+        collection: &str,
+        icon_names: &[String],
+    ) -> Result<FetchedIcons> {
+        let path = format!("/{}.json?icons={}", collection, icon_names.join(","));
+
+        let response = self.get(&path, &[]).await.context(format!(
+            "Failed to fetch icons from collection '{}'",
+            collection
+        ))?;
+
+        // Unknown collection
+        if response.status == StatusCode::NOT_FOUND {
+            return Ok(FetchedIcons {
+                icons: HashMap::new(),
+                not_found: icon_names.to_vec(),
+                source: response.base_url.to_string(),
+            });
+        }
+
+        let response = response.error_for_status()?;
+        // The API answers with a partial collection in the IconifyJSON format
+        let icon_set: IconifyJson = response.json().context("Failed to parse API response")?;
+
+        icon_set.fetch_icons(icon_names, response.base_url)
+    }
+}
+
+/// Connection errors and timeouts are always retried, as are these statuses
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Delay asked by a `Retry-After` header (in seconds, HTTP dates are ignored)
+fn parse_retry_after(headers: &header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Describe a request error with its causes (e.g., "connection refused")
+fn error_message(err: &reqwest::Error) -> String {
+    let mut message = if err.is_timeout() {
+        "timeout".to_string()
+    } else {
+        err.to_string()
+    };
+
+    let mut source = std::error::Error::source(err);
+    while let Some(cause) = source {
+        message = format!("{}: {}", message, cause);
+        source = cause.source();
+    }
+
+    message
+}
+
+/// Split icon names into batches whose `icons` query parameter stays under the URL length limit
+fn chunk_icon_names(icon_names: &[String]) -> Vec<Vec<String>> {
+    let mut chunks: Vec<Vec<String>> = Vec::new();
+    let mut current_length = 0;
+
+    for icon_name in icon_names {
+        let added_length = icon_name.len() + 1; // +1 for the ',' separator
+        match chunks.last_mut() {
+            Some(chunk) if current_length + added_length <= MAX_ICONS_PARAM_LENGTH => {
+                chunk.push(icon_name.clone());
+                current_length += added_length;
+            }
+            _ => {
+                chunks.push(vec![icon_name.clone()]);
+                current_length = added_length;
+            }
+        }
     }
 
-    // /// Fetch multiple icons from the same collection
-    // pub fn fetch_icons(
-    //     &self,
-    //     collection: &str,
-    //     icon_names: &[String],
-    // ) -> Result<HashMap<String, IconifyIcon>> {
-    //     if icon_names.is_empty() {
-    //         return Ok(HashMap::new());
-    //     }
-
-    //     let icons_param = icon_names.join(",");
-    //     let url = format!(
-    //         "{}/{}.json?icons={}",
-    //         self.base_url, collection, icons_param
-    //     );
-
-    //     let response = self.client.get(&url).send().context(format!(
-    //         "Failed to fetch icons from collection '{}'",
-    //         collection
-    //     ))?;
-
-    //     if !response.status().is_success() {
-    //         return Err(anyhow!(
-    //             "API request failed with status {}: {}",
-    //             response.status(),
-    //             response.text().unwrap_or_default()
-    //         ));
-    //     }
-
-    //     let api_response: IconifyApiResponse =
-    //         response.json().context("Failed to parse API response")?;
-
-    //     let default_width = api_response.width.unwrap_or(24);
-    //     let default_height = api_response.height.unwrap_or(24);
-
-    //     // Process each icon and ensure they have dimensions
-    //     let mut result = HashMap::new();
-    //     for (name, mut icon) in api_response.icons {
-    //         let width = icon.width.unwrap_or(default_width);
-    //         let height = icon.height.unwrap_or(default_height);
-
-    //         icon.width = Some(width);
-    //         icon.height = Some(height);
-    //         icon.view_box = Some(
-    //             icon.view_box
-    //                 .clone()
-    //                 .unwrap_or_else(|| format!("0 0 {} {}", width, height)),
-    //         );
-
-    //         result.insert(name, icon);
-    //     }
-
-    //     Ok(result)
-    // }
+    chunks
 }
 
 impl Default for IconifyClient {
@@ -280,7 +840,11 @@ mod tests {
     #[tokio::test]
     async fn test_fetch_icon(#[case] collection: &str, #[case] icon_name: &str) {
         let client = IconifyClient::new().unwrap();
-        let icon = client.fetch_icon(collection, icon_name).await.unwrap();
+        let mut fetched = client
+            .fetch_icons(collection, &[icon_name.to_string()])
+            .await
+            .unwrap();
+        let icon = fetched.icons.remove(icon_name).unwrap();
 
         assert!(!icon.body.is_empty());
         assert!(icon.width.is_some());
@@ -292,11 +856,367 @@ mod tests {
     #[ignore] // Requires internet connection
     async fn test_fetch_nonexistent_icon() {
         let client = IconifyClient::new().unwrap();
+        let fetched = client
+            .fetch_icons("mdi", &["this-icon-does-not-exist-12345".to_string()])
+            .await
+            .unwrap();
+
+        assert!(fetched.icons.is_empty());
+        assert_eq!(fetched.not_found, vec!["this-icon-does-not-exist-12345"]);
+    }
+
+    /// Start a local stand-in server answering every request with
+    /// `handler(request_target, request_headers)`, responses have an `ETag: "v1"` header
+    /// Returns the base URL and a receiver for the request lines
+    fn serve(
+        handler: impl Fn(&str, &str) -> (u16, String) + Send + 'static,
+    ) -> (String, std::sync::mpsc::Receiver<String>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = String::new();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    headers.push_str(&line.to_lowercase());
+                    line.clear();
+                }
+                let request_line = request_line.trim().to_string();
+                let target = request_line.split(' ').nth(1).unwrap_or_default();
+                let (status, body) = handler(target, &headers);
+                let _ = tx.send(request_line.clone());
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, rx)
+    }
+
+    /// Serve the same response to every request
+    fn serve_once(body: &'static str) -> (String, std::sync::mpsc::Receiver<String>) {
+        serve(move |_, _| (200, body.to_string()))
+    }
+
+    #[test]
+    fn test_chunk_icon_names() {
+        let names: Vec<String> = (0..100).map(|i| format!("icon-name-{:03}", i)).collect();
+        let chunks = chunk_icon_names(&names);
+
+        assert!(chunks.len() > 1, "Should be split in several requests");
+        assert_eq!(chunks.concat(), names, "Should keep all names in order");
+        for chunk in &chunks {
+            assert!(chunk.join(",").len() <= MAX_ICONS_PARAM_LENGTH);
+        }
+
+        assert!(chunk_icon_names(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_collections_batched() {
+        let (base_url, requests) = serve(|target, _| {
+            match target {
+            t if t.starts_with("/mdi.json?icons=home,ghost") => (
+                200,
+                r#"{"prefix":"mdi","lastModified":1700000000,"width":24,"height":24,"icons":{"home":{"body":"<path/>"},"wide":{"body":"<rect/>","width":32}},"not_found":["ghost"]}"#
+                    .to_string(),
+            ),
+            t if t.starts_with("/unknown.json") => (404, "404".to_string()),
+            _ => (500, "unexpected request".to_string()),
+        }
+        });
+
+        let client = IconifyClient::with_base_urls(std::slice::from_ref(&base_url)).unwrap();
+        let mut request = BTreeMap::new();
+        request.insert(
+            "mdi".to_string(),
+            vec!["home".to_string(), "ghost".to_string()],
+        );
+        request.insert("unknown".to_string(), vec!["x".to_string()]);
+
+        let results = client.fetch_collections(&request).await;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "mdi");
+        let mdi = results[0].1.as_ref().unwrap();
+        assert_eq!(mdi.source, base_url);
+        let home = mdi.icons.get("home").unwrap();
+        assert_eq!(home.effective_view_box(), "0 0 24 24");
+        assert_eq!(
+            home.origin,
+            IconOrigin::Iconify {
+                last_modified: Some(1700000000)
+            }
+        );
+        assert!(!mdi.icons.contains_key("wide"), "Only requested icons");
+        assert_eq!(mdi.not_found, vec!["ghost"]);
+
+        assert_eq!(results[1].0, "unknown");
+        assert_eq!(results[1].1.as_ref().unwrap().not_found, vec!["x"]);
+
+        // One request per collection
+        assert_eq!(requests.try_iter().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_aliases() {
+        let (base_url, _) = serve_once(
+            r#"{"prefix":"mdi","width":24,"height":24,"icons":{"arrow-left":{"body":"<path/>"}},"aliases":{"arrow-right":{"parent":"arrow-left","hFlip":true}}}"#,
+        );
+
+        let client = IconifyClient::with_base_urls(&[base_url]).unwrap();
+        let fetched = client
+            .fetch_icons("mdi", &["arrow-right".to_string()])
+            .await
+            .unwrap();
+
+        let icon = fetched.icons.get("arrow-right").unwrap();
+        assert_eq!(
+            icon.body,
+            r#"<g transform="translate(24 0) scale(-1 1)"><path/></g>"#
+        );
+        assert!(fetched.not_found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_filters() {
+        let (base_url, request) = serve_once(
+            r#"{"icons":["mdi:home","mdi:home-outline"],"total":2,"limit":32,"start":0,"collections":{"mdi":{"name":"Material Design Icons","total":7000}}}"#,
+        );
+
+        let client = IconifyClient::with_base_urls(&[base_url]).unwrap();
+        let options = SearchOptions {
+            prefix: Some("mdi".to_string()),
+            limit: Some(64),
+            category: None,
+            palette: Some(false),
+        };
+        let result = client.search("home", &options).await.unwrap();
+
+        assert_eq!(result.icons, vec!["mdi:home", "mdi:home-outline"]);
+        assert_eq!(result.total, 2);
+        assert_eq!(
+            result.collections.get("mdi").and_then(|i| i.name.clone()),
+            Some("Material Design Icons".to_string())
+        );
+
+        let request_line = request.recv().unwrap();
+        assert!(
+            request_line.starts_with("GET /search?query=home&prefix=mdi&limit=64&palette=false ")
+        );
+    }
+
+    /// Base URL of a port nobody listens on
+    fn unreachable_url() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_next_api_host() {
+        let (failing_url, failing_requests) = serve(|_, _| (503, "maintenance".to_string()));
+        let (mirror_url, mirror_requests) = serve_once(r#"{"icons":["mdi:home"],"total":1}"#);
+
+        let client = IconifyClient::with_base_urls(&[
+            unreachable_url(),
+            format!("{}/", failing_url),
+            mirror_url,
+        ])
+        .unwrap();
+
         let result = client
-            .fetch_icon("mdi", "this-icon-does-not-exist-12345")
-            .await;
+            .search("home", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.icons, vec!["mdi:home"]);
+        assert_eq!(failing_requests.try_iter().count(), 1);
+
+        // The host that answered is tried first by the next requests
+        client
+            .search("home", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(failing_requests.try_iter().count(), 0);
+        assert_eq!(mirror_requests.try_iter().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_failover_on_client_errors() {
+        let (base_url, _) = serve(|_, _| (400, "bad request".to_string()));
+        let (mirror_url, mirror_requests) = serve_once(r#"{"icons":[],"total":0}"#);
+
+        let client = IconifyClient::with_base_urls(&[base_url, mirror_url]).unwrap();
+
+        let err = client
+            .search("home", &SearchOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("400"));
+        assert_eq!(mirror_requests.try_iter().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_all_api_hosts_unreachable() {
+        let client = IconifyClient::with_base_urls(&[unreachable_url(), unreachable_url()])
+            .unwrap()
+            .with_retry_policy(fast_retries(2));
+
+        let err = client
+            .search("home", &SearchOptions::default())
+            .await
+            .unwrap_err();
+        let network_error = err.downcast_ref::<NetworkError>().unwrap();
+        assert_eq!(network_error.attempts, 3);
+        assert!(network_error.message.contains("onnection refused"));
+
+        assert!(IconifyClient::with_base_urls(&[]).is_err());
+    }
 
-        assert!(result.is_err());
+    /// Retry policy without (noticeable) delays
+    fn fast_retries(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        }
+    }
+
+    #[rstest]
+    #[case(vec![503, 502], true)]
+    #[case(vec![429], true)]
+    #[case(vec![503, 503, 503], false)]
+    #[tokio::test]
+    async fn test_retry_transient_failures(#[case] failures: Vec<u16>, #[case] succeeds: bool) {
+        let requests = (failures.len() + 1).min(3);
+        let count = std::sync::atomic::AtomicUsize::new(0);
+        let (base_url, received) =
+            serve(
+                move |_, _| match failures.get(count.fetch_add(1, Ordering::SeqCst)) {
+                    Some(status) => (*status, "unavailable".to_string()),
+                    None => (200, r#"{"icons":["mdi:home"],"total":1}"#.to_string()),
+                },
+            );
+
+        let client = IconifyClient::with_base_urls(&[base_url])
+            .unwrap()
+            .with_retry_policy(fast_retries(2));
+
+        let result = client.search("home", &SearchOptions::default()).await;
+
+        assert_eq!(received.try_iter().count(), requests);
+        match result {
+            Ok(result) => {
+                assert!(succeeds);
+                assert_eq!(result.icons, vec!["mdi:home"]);
+            }
+            Err(err) => {
+                assert!(!succeeds);
+                assert!(err.downcast_ref::<NetworkError>().is_some());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_not_found() {
+        let (base_url, received) = serve(|_, _| (404, "404".to_string()));
+        let client = IconifyClient::with_base_urls(&[base_url])
+            .unwrap()
+            .with_retry_policy(fast_retries(2));
+
+        let fetched = client
+            .fetch_icons("unknown", &["x".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(fetched.not_found, vec!["x"]);
+        assert_eq!(received.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        for (round, max) in [(0, 100), (1, 200), (2, 400), (3, 500), (10, 500)] {
+            let delay = policy.backoff(round);
+            assert!(delay >= Duration::from_millis(max / 2), "round {}", round);
+            assert!(delay <= Duration::from_millis(max), "round {}", round);
+        }
+    }
+
+    #[rstest]
+    #[case(Some("120"), Some(Duration::from_secs(120)))]
+    #[case(Some(" 0 "), Some(Duration::ZERO))]
+    #[case(Some("Wed, 21 Oct 2015 07:28:00 GMT"), None)]
+    #[case(None, None)]
+    fn test_parse_retry_after(#[case] value: Option<&str>, #[case] expected: Option<Duration>) {
+        let mut headers = header::HeaderMap::new();
+        if let Some(value) = value {
+            headers.insert(header::RETRY_AFTER, value.parse().unwrap());
+        }
+
+        assert_eq!(parse_retry_after(&headers), expected);
+    }
+
+    #[tokio::test]
+    async fn test_cache_revalidation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (base_url, requests) = serve(|_, headers| {
+            if headers.contains("if-none-match: \"v1\"") {
+                (304, String::new())
+            } else {
+                (200, r#"{"icons":["mdi:home"],"total":1}"#.to_string())
+            }
+        });
+
+        let client = IconifyClient::with_base_urls(std::slice::from_ref(&base_url))
+            .unwrap()
+            .with_cache(HttpCache::new(temp_dir.path().to_path_buf()), false);
+
+        // Fetched then revalidated (304 Not Modified, served from the cache)
+        for _ in 0..2 {
+            let result = client
+                .search("home", &SearchOptions::default())
+                .await
+                .unwrap();
+            assert_eq!(result.icons, vec!["mdi:home"]);
+        }
+        assert_eq!(requests.try_iter().count(), 2);
+
+        // Offline: served from the cache without any request
+        let offline_client = IconifyClient::with_base_urls(&[unreachable_url(), base_url])
+            .unwrap()
+            .with_cache(HttpCache::new(temp_dir.path().to_path_buf()), true);
+
+        let result = offline_client
+            .search("home", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.icons, vec!["mdi:home"]);
+        assert_eq!(requests.try_iter().count(), 0);
+
+        let err = offline_client
+            .search("house", &SearchOptions::default())
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("not in the cache (offline mode)"));
     }
 
     #[rstest]
@@ -0,0 +1,278 @@
+use anyhow::{Context, Result, anyhow};
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use crate::lockfile::hash_bytes;
+
+/// Extensions of gzipped tarballs (e.g., `npm pack` packages)
+const TAR_GZ_EXTENSIONS: &[&str] = &[".tgz", ".tar.gz"];
+
+/// Extensions of zip archives
+const ZIP_EXTENSIONS: &[&str] = &[".zip"];
+
+/// The SVG files of an archive (npm tarball or zip), read in memory without extracting it
+#[derive(Debug, Clone)]
+pub struct Archive {
+    /// SHA-256 of the archive file, formatted as "sha256:<hex>"
+    pub hash: String,
+    /// Content of the SVG files, keyed by their path in the archive
+    pub svg_files: BTreeMap<String, String>,
+}
+
+/// Check whether a path is a supported archive, by its extension
+pub fn is_archive(path: &Path) -> bool {
+    let name = file_name(path);
+    TAR_GZ_EXTENSIONS
+        .iter()
+        .chain(ZIP_EXTENSIONS)
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Default collection name of an archive: its file name without extension and version
+/// Example: "lucide-static-0.400.0.tgz" → "lucide-static"
+pub fn collection_name(path: &Path) -> Result<String> {
+    let name = file_name(path);
+    let stem = TAR_GZ_EXTENSIONS
+        .iter()
+        .chain(ZIP_EXTENSIONS)
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(&name);
+
+    // Strip a trailing "-<version>"
+    let stem = match stem.rsplit_once('-') {
+        Some((name, version))
+            if !name.is_empty() && version.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            name
+        }
+        _ => stem,
+    };
+
+    if stem.is_empty() {
+        return Err(anyhow!("Invalid archive name: {}", path.display()));
+    }
+
+    Ok(stem.to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+impl Archive {
+    /// Read the SVG files of an archive
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes =
+            fs::read(path).context(format!("Failed to read archive: {}", path.display()))?;
+        let name = file_name(path);
+
+        let svg_files = if TAR_GZ_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+            read_tar_gz(&bytes)
+        } else if ZIP_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+            read_zip(&bytes)
+        } else {
+            Err(anyhow!("Unsupported archive format"))
+        }
+        .context(format!("Failed to read archive: {}", path.display()))?;
+
+        Ok(Self {
+            hash: hash_bytes(&bytes),
+            svg_files,
+        })
+    }
+
+    /// List the SVG files under `dir` with their icon names, `dir` being relative to the
+    /// archive root or to its single top-level directory (e.g., `package/` of npm tarballs)
+    /// Example: dir="icons/", entry="package/icons/arrows/left.svg" → "arrows-left"
+    pub fn scan(&self, dir: Option<&str>) -> Vec<(&str, String)> {
+        let root = self.root_dir();
+        let dir = dir
+            .map(|dir| dir.trim_matches('/'))
+            .filter(|dir| !dir.is_empty());
+
+        self.svg_files
+            .keys()
+            .filter_map(|entry| {
+                let relative = match dir {
+                    Some(dir) => root
+                        .and_then(|root| entry.strip_prefix(root))
+                        .and_then(|path| path.strip_prefix(dir))
+                        .or_else(|| entry.strip_prefix(dir))?
+                        .strip_prefix('/')?,
+                    None => root
+                        .and_then(|root| entry.strip_prefix(root))
+                        .unwrap_or(entry),
+                };
+
+                let icon_name = relative.strip_suffix(".svg")?.replace('/', "-");
+                (!icon_name.is_empty()).then_some((entry.as_str(), icon_name))
+            })
+            .collect()
+    }
+
+    /// The single top-level directory of the SVG files (with a trailing `/`), if any
+    fn root_dir(&self) -> Option<&str> {
+        let first = self.svg_files.keys().next()?;
+        let root = &first[..first.find('/')? + 1];
+
+        self.svg_files
+            .keys()
+            .all(|entry| entry.starts_with(root))
+            .then_some(root)
+    }
+}
+
+fn is_svg(entry: &str) -> bool {
+    entry.ends_with(".svg")
+}
+
+fn read_tar_gz(bytes: &[u8]) -> Result<BTreeMap<String, String>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(bytes)));
+    let mut svg_files = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+        if is_svg(&entry_path) {
+            let mut content = String::new();
+            entry
+                .read_to_string(&mut content)
+                .context(format!("Failed to read {}", entry_path))?;
+            svg_files.insert(entry_path, content);
+        }
+    }
+
+    Ok(svg_files)
+}
+
+fn read_zip(bytes: &[u8]) -> Result<BTreeMap<String, String>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut svg_files = BTreeMap::new();
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if !file.is_file() {
+            continue;
+        }
+
+        let entry_path = file.name().to_string();
+        if is_svg(&entry_path) {
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .context(format!("Failed to read {}", entry_path))?;
+            svg_files.insert(entry_path, content);
+        }
+    }
+
+    Ok(svg_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Write a gzipped tarball (or a zip, by extension) of files
+    fn write_archive(path: &Path, files: &[(&str, &str)]) -> Result<()> {
+        let file = fs::File::create(path)?;
+
+        if file_name(path).ends_with(".zip") {
+            let mut zip = zip::ZipWriter::new(file);
+            for (name, content) in files {
+                zip.start_file(*name, zip::write::SimpleFileOptions::default())?;
+                zip.write_all(content.as_bytes())?;
+            }
+            zip.finish()?;
+        } else {
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut tar = tar::Builder::new(encoder);
+            for (name, content) in files {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                tar.append_data(&mut header, name, content.as_bytes())?;
+            }
+            tar.into_inner()?.finish()?;
+        }
+
+        Ok(())
+    }
+
+    const FILES: &[(&str, &str)] = &[
+        ("package/package.json", "{}"),
+        ("package/icons/home.svg", "<svg/>"),
+        ("package/icons/arrows/left.svg", "<svg/>"),
+        ("package/sprite.svg", "<svg/>"),
+    ];
+
+    #[rstest]
+    #[case("icons.tgz")]
+    #[case("icons.tar.gz")]
+    #[case("icons.zip")]
+    fn test_read_archive(#[case] name: &str) -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(name);
+        write_archive(&path, FILES)?;
+
+        let archive = Archive::read(&path)?;
+        assert_eq!(
+            archive.svg_files.keys().collect::<Vec<_>>(),
+            vec![
+                "package/icons/arrows/left.svg",
+                "package/icons/home.svg",
+                "package/sprite.svg"
+            ]
+        );
+        assert_eq!(archive.hash, hash_bytes(&fs::read(&path)?));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(Some("icons/"), vec![("package/icons/arrows/left.svg", "arrows-left"), ("package/icons/home.svg", "home")])]
+    #[case(Some("package/icons"), vec![("package/icons/arrows/left.svg", "arrows-left"), ("package/icons/home.svg", "home")])]
+    #[case(Some("icons/arrows"), vec![("package/icons/arrows/left.svg", "left")])]
+    #[case(Some("other"), vec![])]
+    #[case(None, vec![("package/icons/arrows/left.svg", "icons-arrows-left"), ("package/icons/home.svg", "icons-home"), ("package/sprite.svg", "sprite")])]
+    fn test_scan(#[case] dir: Option<&str>, #[case] expected: Vec<(&str, &str)>) {
+        let archive = Archive {
+            hash: String::new(),
+            svg_files: FILES
+                .iter()
+                .filter(|(name, _)| is_svg(name))
+                .map(|(name, content)| (name.to_string(), content.to_string()))
+                .collect(),
+        };
+
+        let scanned = archive.scan(dir);
+        let scanned: Vec<(&str, &str)> = scanned
+            .iter()
+            .map(|(entry, name)| (*entry, name.as_str()))
+            .collect();
+        assert_eq!(scanned, expected);
+    }
+
+    #[rstest]
+    #[case("lucide-static-0.400.0.tgz", "lucide-static")]
+    #[case("heroicons.zip", "heroicons")]
+    #[case("tabler-icons-v3.tar.gz", "tabler-icons-v3")]
+    #[case("icons-2.zip", "icons")]
+    fn test_collection_name(#[case] name: &str, #[case] expected: &str) -> Result<()> {
+        assert!(is_archive(Path::new(name)));
+        assert_eq!(collection_name(Path::new(name))?, expected);
+        Ok(())
+    }
+}
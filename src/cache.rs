@@ -0,0 +1,173 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// On-disk cache of the Iconify API responses, keyed by URL
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+/// A cached response, with the validators used to revalidate it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    /// `ETag` header of the response (sent back as `If-None-Match`)
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// `Last-Modified` header of the response (sent back as `If-Modified-Since`)
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Summary of the cache content
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheInfo {
+    pub entries: usize,
+    /// Total size in bytes
+    pub size: u64,
+}
+
+impl HttpCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Default cache directory: `$XDG_CACHE_HOME/dioxus-iconify`, `~/.cache/dioxus-iconify`
+    /// (or `%LOCALAPPDATA%\dioxus-iconify` on Windows)
+    pub fn default_dir() -> Option<PathBuf> {
+        let env_dir = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+
+        env_dir("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env_dir("LOCALAPPDATA").map(PathBuf::from))
+            .or_else(|| env_dir("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map(|dir| dir.join("dioxus-iconify"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the cached response of a URL (unreadable entries are ignored)
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let content = fs::read_to_string(self.entry_path(url)).ok()?;
+        serde_json::from_str::<CachedResponse>(&content)
+            .ok()
+            .filter(|cached| cached.url == url)
+    }
+
+    /// Store the response of a URL
+    pub fn put(&self, response: &CachedResponse) -> Result<()> {
+        fs::create_dir_all(&self.dir).context(format!(
+            "Failed to create cache directory {}",
+            self.dir.display()
+        ))?;
+
+        let path = self.entry_path(&response.url);
+        let content = serde_json::to_string(response).context("Failed to serialize response")?;
+        fs::write(&path, content).context(format!("Failed to write {}", path.display()))
+    }
+
+    /// Number of entries and total size of the cache
+    pub fn info(&self) -> Result<CacheInfo> {
+        let mut info = CacheInfo::default();
+        for path in self.entry_paths()? {
+            info.entries += 1;
+            info.size += fs::metadata(&path)?.len();
+        }
+
+        Ok(info)
+    }
+
+    /// Remove every entry, returns the number of removed entries
+    pub fn clear(&self) -> Result<usize> {
+        let paths = self.entry_paths()?;
+        for path in &paths {
+            fs::remove_file(path).context(format!("Failed to remove {}", path.display()))?;
+        }
+
+        Ok(paths.len())
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(format!("{}.json", hex))
+    }
+
+    fn entry_paths(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        for entry in
+            fs::read_dir(&self.dir).context(format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn response(url: &str) -> CachedResponse {
+        CachedResponse {
+            url: url.to_string(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: r#"{"icons":{}}"#.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_put_get() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = HttpCache::new(temp_dir.path().join("cache"));
+
+        assert_eq!(cache.get("https://api.iconify.design/mdi.json"), None);
+
+        cache.put(&response("https://api.iconify.design/mdi.json"))?;
+        assert_eq!(
+            cache.get("https://api.iconify.design/mdi.json"),
+            Some(response("https://api.iconify.design/mdi.json"))
+        );
+        assert_eq!(cache.get("https://api.iconify.design/lucide.json"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_info_clear() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = HttpCache::new(temp_dir.path().join("cache"));
+
+        assert_eq!(cache.info()?, CacheInfo::default());
+        assert_eq!(cache.clear()?, 0);
+
+        cache.put(&response("https://api.iconify.design/mdi.json"))?;
+        cache.put(&response("https://api.iconify.design/lucide.json"))?;
+
+        let info = cache.info()?;
+        assert_eq!(info.entries, 2);
+        assert!(info.size > 0);
+
+        assert_eq!(cache.clear()?, 2);
+        assert_eq!(cache.info()?.entries, 0);
+        assert_eq!(cache.get("https://api.iconify.design/mdi.json"), None);
+
+        Ok(())
+    }
+}
@@ -4,8 +4,12 @@ use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::api::{IconifyCollectionInfo, IconifyIcon};
+use crate::api::{DEFAULT_ICON_SIZE, IconOrigin, IconifyCollectionInfo, IconifyIcon};
+use crate::iconify_json::{IconProps, IconifyJson, IconifyJsonIcon};
+use crate::lockfile::{LOCKFILE_NAME, LOCKFILE_VERSION, LockedIcon, Lockfile, hash_body};
+use crate::manifest::IconOptions;
 use crate::naming::IconIdentifier;
+use crate::svg;
 
 const MOD_RS_TEMPLATE: &str = indoc! {r#"// Auto-generated by dioxus-iconify - DO NOT EDIT
     use dioxus::prelude::*;
@@ -25,6 +29,10 @@ const MOD_RS_TEMPLATE: &str = indoc! {r#"// Auto-generated by dioxus-iconify - D
         /// Optional size to set both width and height
         #[props(default, into)]
         size: String,
+        /// Give the IDs of the body a per-instance suffix, for icons with gradients,
+        /// clip paths or masks rendered several times on a page
+        #[props(default)]
+        unique_ids: bool,
         /// Additional attributes to extend the svg element
         #[props(extends = SvgAttributes)]
         attributes: Vec<Attribute>,
@@ -35,25 +43,77 @@ const MOD_RS_TEMPLATE: &str = indoc! {r#"// Auto-generated by dioxus-iconify - D
             (size.as_str(), size.as_str())
         };
 
+        let body = if unique_ids {
+            // IDs are prefixed with the icon name on import ("mdi:home" → "mdi_home_"), the
+            // scope of the instance is the same when rendered on the server and hydrated
+            let prefix = format!("{}_", data.name.replace(':', "_"));
+            scope_ids(data.body, &prefix, dioxus::core::current_scope_id().0)
+        } else {
+            data.body.to_string()
+        };
+
         rsx! {
             svg {
                 view_box: "{data.view_box}",
                 width: "{width}",
                 height: "{height}",
-                dangerous_inner_html: "{data.body}",
+                dangerous_inner_html: "{body}",
                 ..attributes,
             }
         }
     }
+
+    /// Insert the scope of an instance after the prefix of the IDs of a body, and of the
+    /// references to them ("mdi_home_a" → "mdi_home_3_a")
+    fn scope_ids(body: &str, prefix: &str, scope: usize) -> String {
+        let mut scoped = String::with_capacity(body.len());
+        let mut rest = body;
+        while let Some(start) = rest.find(prefix) {
+            let (before, after) = rest.split_at(start + prefix.len());
+            scoped.push_str(before);
+            if is_id_position(&scoped[..scoped.len() - prefix.len()]) {
+                scoped.push_str(&format!("{}_", scope));
+            }
+            rest = after;
+        }
+        scoped.push_str(rest);
+        scoped
+    }
+
+    /// Whether an ID starts after `before`: in an `id` attribute, an ARIA ID list, or a `#id`
+    /// reference (`href`, `url(#id)`, stylesheet selectors)
+    fn is_id_position(before: &str) -> bool {
+        if before.ends_with('#') {
+            return true;
+        }
+        let Some(start) = before.rfind("=\"") else {
+            return false;
+        };
+        let value = &before[start + 2..];
+        let name = before[..start].rsplit(' ').next().unwrap_or_default();
+        match name {
+            "id" => value.is_empty(),
+            "aria-labelledby" | "aria-describedby" => {
+                !value.contains('"') && (value.is_empty() || value.ends_with(' '))
+            }
+            _ => false,
+        }
+    }
     "#};
 
+/// First line of every collection file generated by this tool
+const GENERATED_MARKER: &str = "/// Auto-generated by dioxus-iconify - DO NOT EDIT";
+
 /// Represents a generated icon constant
 #[derive(Debug, Clone)]
 struct IconConst {
     name: String,
     full_icon_name: String,
     body: String,
+    /// `left top width height`, the only carrier of the offsets and of the exact
+    /// (fractional) box, exported back to `left`/`top`/`width`/`height`
     view_box: String,
+    /// Rendered size (`width`/`height` attributes), formatted like in the viewBox
     width: String,
     height: String,
 }
@@ -64,23 +124,23 @@ impl IconConst {
             name: identifier.to_const_name(),
             full_icon_name: identifier.full_name.clone(),
             body: icon.body.clone(),
-            view_box: icon
-                .view_box
-                .clone()
-                .unwrap_or_else(|| "0 0 24 24".to_string()),
-            width: icon.width.unwrap_or(24).to_string(),
-            height: icon.height.unwrap_or(24).to_string(),
+            view_box: icon.effective_view_box(),
+            width: icon.width.unwrap_or(DEFAULT_ICON_SIZE).to_string(),
+            height: icon.height.unwrap_or(DEFAULT_ICON_SIZE).to_string(),
         }
     }
 
     fn to_rust_code(&self) -> String {
+        // Use enough '#' so that the raw string can't be terminated by the body itself
+        let hashes = raw_string_hashes(&self.body);
+
         // we use non upper case to be able to switch/wrap to struct or enum i the future
         formatdoc! { "
 
             #[allow(non_upper_case_globals)]
             pub const {}: IconData = IconData {{
                 name: \"{}\",
-                body: r#\"{}\"#,
+                body: r{}\"{}\"{},
                 view_box: \"{}\",
                 width: \"{}\",
                 height: \"{}\",
@@ -88,7 +148,9 @@ impl IconConst {
             ",
             self.name,
             self.full_icon_name,
+            hashes,
             self.body,
+            hashes,
             self.view_box,
             self.width,
             self.height
@@ -109,6 +171,14 @@ impl Generator {
 
     /// List all generated icons grouped by collection
     pub fn list_icons(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        self.collect_icons(false)
+    }
+
+    /// Collect icons grouped by collection, optionally only the ones generated by this tool
+    /// (user-written modules like `app.rs` are then ignored)
+    ///
+    /// Generated icons come from the lockfile, user-written modules are scanned.
+    fn collect_icons(&self, generated_only: bool) -> Result<BTreeMap<String, Vec<String>>> {
         let mut icons_by_collection: BTreeMap<String, Vec<String>> = BTreeMap::new();
 
         // Check if icons directory exists
@@ -116,24 +186,24 @@ impl Generator {
             return Ok(icons_by_collection);
         }
 
-        // Read all .rs files in the icons directory (except mod.rs)
-        let entries = fs::read_dir(&self.icons_dir).context("Failed to read icons directory")?;
-
-        for entry in entries {
-            let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
+        for full_name in self.lockfile()?.icons.keys() {
+            let identifier = IconIdentifier::parse(full_name)?;
+            icons_by_collection
+                .entry(identifier.module_name())
+                .or_default()
+                .push(full_name.clone());
+        }
 
-            // Skip if not a file or if it's mod.rs
-            if !path.is_file() || path.file_name() == Some("mod.rs".as_ref()) {
-                continue;
-            }
+        if generated_only {
+            return Ok(icons_by_collection);
+        }
 
-            // Skip if not a .rs file
-            if path.extension() != Some("rs".as_ref()) {
+        for path in self.collection_files()? {
+            if is_generated_file(&path)? {
                 continue;
             }
 
-            // Parse the collection file
+            // Parse the user-written module
             let icons = self.parse_collection_file(&path)?;
 
             // Get collection name from file name
@@ -152,6 +222,89 @@ impl Generator {
         Ok(icons_by_collection)
     }
 
+    /// List the .rs files of the icons directory (except mod.rs), sorted by name
+    fn collection_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+
+        let entries = fs::read_dir(&self.icons_dir).context("Failed to read icons directory")?;
+        for entry in entries {
+            let path = entry.context("Failed to read directory entry")?.path();
+
+            // Skip if not a .rs file or if it's mod.rs
+            if !path.is_file()
+                || path.file_name() == Some("mod.rs".as_ref())
+                || path.extension() != Some("rs".as_ref())
+            {
+                continue;
+            }
+
+            files.push(path);
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// Load the lockfile (icons.lock)
+    ///
+    /// Icons directories generated before the lockfile existed are bootstrapped
+    /// from the generated files (their origin is assumed to be the Iconify API).
+    /// Lockfiles of version 1 get their collection info from the headers of the generated files.
+    pub fn lockfile(&self) -> Result<Lockfile> {
+        let loaded = Lockfile::load(&self.lockfile_path())?;
+        if let Some(lockfile) = &loaded
+            && lockfile.version >= LOCKFILE_VERSION
+        {
+            return Ok(lockfile.clone());
+        }
+
+        let mut lockfile = loaded.unwrap_or_default();
+        lockfile.version = LOCKFILE_VERSION;
+        if !self.icons_dir.exists() {
+            return Ok(lockfile);
+        }
+
+        let bootstrap = lockfile.icons.is_empty();
+        for path in self.collection_files()? {
+            if !is_generated_file(&path)? {
+                continue;
+            }
+            let icons = self.parse_collection_file(&path)?;
+            let content =
+                fs::read_to_string(&path).context(format!("Failed to read file {:?}", path))?;
+            if let (Some(collection), Some(info)) = (
+                collection_of(&icons),
+                parse_collection_info_comment(&content),
+            ) {
+                lockfile.collections.insert(collection, info);
+            }
+            if !bootstrap {
+                continue;
+            }
+
+            for icon in icons.into_values() {
+                lockfile.icons.insert(
+                    icon.full_icon_name.clone(),
+                    LockedIcon {
+                        origin: IconOrigin::default(),
+                        collection_version: None,
+                        width: icon.width,
+                        height: icon.height,
+                        view_box: icon.view_box,
+                        hash: hash_body(&icon.body),
+                        options: None,
+                    },
+                );
+            }
+        }
+
+        Ok(lockfile)
+    }
+
+    fn lockfile_path(&self) -> PathBuf {
+        self.icons_dir.join(LOCKFILE_NAME)
+    }
+
     /// Get all icon identifiers from generated files
     pub fn get_all_icon_identifiers(&self) -> Result<Vec<String>> {
         let icons_by_collection = self.list_icons()?;
@@ -164,6 +317,11 @@ impl Generator {
         Ok(all_icons)
     }
 
+    /// Get icon identifiers from files generated by this tool (user-written modules excluded)
+    pub fn get_generated_icon_identifiers(&self) -> Result<Vec<String>> {
+        Ok(self.collect_icons(true)?.into_values().flatten().collect())
+    }
+
     /// Initialize the icons directory with mod.rs if it doesn't exist
     pub fn init(&self) -> Result<()> {
         // Create icons directory if it doesn't exist
@@ -200,18 +358,51 @@ impl Generator {
                 .push((identifier.clone(), icon.clone()));
         }
 
-        // Generate/update each collection file
+        // Generate/update each collection file, with the recorded collection info
+        // unless newer info is given
+        let mut lockfile = self.lockfile()?;
         for (collection, collection_icons) in &icons_by_collection {
-            let info = collection_info.get(collection);
+            if let Some(info) = collection_info.get(collection) {
+                lockfile
+                    .collections
+                    .insert(collection.clone(), info.clone());
+            }
+            let info = lockfile.collections.get(collection);
             self.update_collection_file(collection, collection_icons, info)?;
         }
 
         // Update mod.rs with module declarations
         self.update_mod_rs(&icons_by_collection.keys().cloned().collect::<Vec<_>>())?;
 
+        // Record provenance and hashes
+        for (identifier, icon) in icons {
+            let collection_version = collection_info
+                .get(&identifier.collection)
+                .and_then(|info| info.version.clone());
+            lockfile.icons.insert(
+                identifier.full_name.clone(),
+                LockedIcon::new(icon, collection_version),
+            );
+        }
+        lockfile.save(&self.lockfile_path())?;
+
         Ok(())
     }
 
+    /// Record the manifest options applied to icons by `sync` in the lockfile
+    pub fn record_options(
+        &self,
+        options: impl IntoIterator<Item = (String, Option<IconOptions>)>,
+    ) -> Result<()> {
+        let mut lockfile = self.lockfile()?;
+        for (full_name, options) in options {
+            if let Some(locked) = lockfile.icons.get_mut(&full_name) {
+                locked.options = options;
+            }
+        }
+        lockfile.save(&self.lockfile_path())
+    }
+
     /// Regenerate mod.rs with the latest template
     /// This is useful for updating the Icon component definition after CLI updates
     pub fn regenerate_mod_rs(&self) -> Result<()> {
@@ -229,20 +420,306 @@ impl Generator {
         let existing_modules = extract_module_declarations(&content);
 
         // Regenerate mod.rs with latest template
-        let mut new_content = MOD_RS_TEMPLATE.to_string();
-        new_content.push('\n');
+        write_if_changed(&mod_rs_path, &render_mod_rs(&existing_modules))
+            .context("Failed to update mod.rs")?;
 
-        // Add module declarations in alphabetical order
-        let mut sorted_modules: Vec<_> = existing_modules.iter().collect();
-        sorted_modules.sort_by_key(|(name, _)| *name);
+        Ok(())
+    }
 
-        for (module, visibility) in sorted_modules {
-            new_content.push_str(&format!("{}mod {};\n", visibility, module));
+    /// Remove icons from the generated code
+    ///
+    /// An identifier with `*` as icon name (e.g. `mdi:*`) removes the whole collection.
+    /// Collection files left without icons are deleted and their module declaration
+    /// is dropped from mod.rs.
+    /// Returns the full names of the removed icons.
+    pub fn remove_icons(&self, identifiers: &[IconIdentifier]) -> Result<Vec<String>> {
+        // Group requested icons by collection
+        let mut requested_by_collection: BTreeMap<String, Vec<&IconIdentifier>> = BTreeMap::new();
+        for identifier in identifiers {
+            requested_by_collection
+                .entry(identifier.collection.clone())
+                .or_default()
+                .push(identifier);
         }
 
-        fs::write(&mod_rs_path, new_content).context("Failed to update mod.rs")?;
+        let mut lockfile = self.lockfile()?;
+        let mut removed = Vec::new();
+        let mut emptied_modules = Vec::new();
 
-        Ok(())
+        for (collection, requested) in &requested_by_collection {
+            let module_name = collection.replace('-', "_");
+            let file_path = self.icons_dir.join(format!("{}.rs", module_name));
+
+            if !file_path.exists() {
+                continue;
+            }
+
+            let mut icons = self.parse_collection_file(&file_path)?;
+            let count_before = icons.len();
+
+            icons.retain(|_, icon| {
+                let matched = requested
+                    .iter()
+                    .any(|id| id.icon_name == "*" || id.full_name == icon.full_icon_name);
+                if matched {
+                    removed.push(icon.full_icon_name.clone());
+                }
+                !matched
+            });
+
+            if icons.len() == count_before {
+                continue;
+            }
+
+            if icons.is_empty() {
+                fs::remove_file(&file_path)
+                    .context(format!("Failed to remove collection file {:?}", file_path))?;
+                println!("✓ Removed {}.rs", module_name);
+                lockfile.collections.remove(collection);
+                emptied_modules.push(module_name);
+            } else {
+                let new_content = self.generate_collection_file(
+                    collection,
+                    &icons,
+                    lockfile.collections.get(collection),
+                )?;
+                write_if_changed(&file_path, &new_content)
+                    .context(format!("Failed to write collection file {:?}", file_path))?;
+                println!(
+                    "✓ Updated {}.rs, removed {} icon(s)",
+                    module_name,
+                    count_before - icons.len()
+                );
+            }
+        }
+
+        if !emptied_modules.is_empty() {
+            self.remove_from_mod_rs(&emptied_modules)?;
+        }
+
+        if !removed.is_empty() {
+            lockfile
+                .icons
+                .retain(|full_name, _| !removed.contains(full_name));
+            lockfile.save(&self.lockfile_path())?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Recover the vendored icons as IconifyJSON collections, keyed by prefix
+    ///
+    /// Icons of user-written modules are included, the collection info is read back from the
+    /// headers of generated files.
+    pub fn export_iconify_json(&self) -> Result<BTreeMap<String, IconifyJson>> {
+        let mut collections: BTreeMap<String, IconifyJson> = BTreeMap::new();
+
+        if !self.icons_dir.exists() {
+            return Ok(collections);
+        }
+
+        for path in self.collection_files()? {
+            let content =
+                fs::read_to_string(&path).context(format!("Failed to read file {:?}", path))?;
+            let info = parse_collection_info_comment(&content);
+
+            for icon in self.parse_collection_file(&path)?.into_values() {
+                let identifier = IconIdentifier::parse(&icon.full_icon_name)?;
+                let (left, top, width, height) = svg::parse_viewbox(&icon.view_box)
+                    .context(format!("Invalid viewBox of {}", icon.full_icon_name))?;
+
+                let collection = collections
+                    .entry(identifier.collection.clone())
+                    .or_insert_with(|| IconifyJson {
+                        prefix: identifier.collection.clone(),
+                        ..Default::default()
+                    });
+                if collection.info.is_none() {
+                    collection.info = info.clone();
+                }
+                collection.icons.insert(
+                    identifier.icon_name,
+                    IconifyJsonIcon {
+                        body: icon.body,
+                        props: IconProps {
+                            left: (left != 0.0).then_some(left),
+                            top: (top != 0.0).then_some(top),
+                            width: Some(width),
+                            height: Some(height),
+                            ..Default::default()
+                        },
+                    },
+                );
+            }
+        }
+
+        Ok(collections)
+    }
+
+    /// Check that generated files are exactly what the generator would produce, without writing
+    ///
+    /// Returns the problems found, keyed by file name (empty when everything is up to date).
+    /// User-written modules (files without the generated marker) are only checked for their
+    /// declaration in mod.rs.
+    pub fn check(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut problems: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        let mod_rs_path = self.icons_dir.join("mod.rs");
+        if !mod_rs_path.exists() {
+            problems
+                .entry("mod.rs".to_string())
+                .or_default()
+                .push("missing".to_string());
+            return Ok(problems);
+        }
+
+        let mod_content = fs::read_to_string(&mod_rs_path).context("Failed to read mod.rs")?;
+        let declared_modules = extract_module_declarations(&mod_content);
+        if mod_content != render_mod_rs(&declared_modules) {
+            problems
+                .entry("mod.rs".to_string())
+                .or_default()
+                .push("differs from the current template".to_string());
+        }
+
+        let module_files: Vec<(String, PathBuf)> = self
+            .collection_files()?
+            .into_iter()
+            .filter_map(|path| {
+                let module_name = path.file_stem()?.to_str()?.to_string();
+                Some((module_name, path))
+            })
+            .collect();
+
+        // Compare against the recorded icons (no network)
+        let lockfile = Lockfile::load(&self.lockfile_path())?;
+        if lockfile.is_none() {
+            problems
+                .entry(LOCKFILE_NAME.to_string())
+                .or_default()
+                .push("missing".to_string());
+        }
+        let mut unseen_locked: Vec<&String> = lockfile
+            .as_ref()
+            .map(|l| l.icons.keys().collect())
+            .unwrap_or_default();
+
+        for (module_name, path) in &module_files {
+            let file_name = format!("{}.rs", module_name);
+
+            if !declared_modules.contains_key(module_name) {
+                problems
+                    .entry(file_name.clone())
+                    .or_default()
+                    .push("not declared in mod.rs".to_string());
+            }
+
+            if !is_generated_file(path)? {
+                continue;
+            }
+
+            let content =
+                fs::read_to_string(path).context(format!("Failed to read file {:?}", path))?;
+            let icons = self.parse_collection_file(path)?;
+
+            for (const_name, icon) in &icons {
+                match IconIdentifier::parse(&icon.full_icon_name) {
+                    Ok(identifier) if identifier.module_name() != *module_name => {
+                        problems.entry(file_name.clone()).or_default().push(format!(
+                            "{} belongs to another collection",
+                            icon.full_icon_name
+                        ));
+                    }
+                    Ok(identifier) if identifier.to_const_name() != *const_name => {
+                        problems.entry(file_name.clone()).or_default().push(format!(
+                            "{} should be named {}",
+                            const_name,
+                            identifier.to_const_name()
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        problems
+                            .entry(file_name.clone())
+                            .or_default()
+                            .push(format!("{}: {}", const_name, e));
+                    }
+                }
+            }
+
+            if let Some(lockfile) = &lockfile {
+                for icon in icons.values() {
+                    unseen_locked.retain(|name| **name != icon.full_icon_name);
+                    match lockfile.icons.get(&icon.full_icon_name) {
+                        None => {
+                            problems.entry(file_name.clone()).or_default().push(format!(
+                                "{} is not recorded in {}",
+                                icon.full_icon_name, LOCKFILE_NAME
+                            ));
+                        }
+                        Some(locked)
+                            if locked.hash != hash_body(&icon.body)
+                                || locked.width != icon.width
+                                || locked.height != icon.height
+                                || locked.view_box != icon.view_box =>
+                        {
+                            problems.entry(file_name.clone()).or_default().push(format!(
+                                "{} differs from {} (hand-edited?)",
+                                icon.full_icon_name, LOCKFILE_NAME
+                            ));
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+
+            // The header is rebuilt from the recorded collection info, not read from the file
+            let collection = collection_of(&icons).unwrap_or_else(|| module_name.replace('_', "-"));
+            let expected = self.generate_collection_file(
+                &collection,
+                &icons,
+                lockfile
+                    .as_ref()
+                    .and_then(|lockfile| lockfile.collections.get(&collection)),
+            )?;
+            if content != expected {
+                problems
+                    .entry(file_name)
+                    .or_default()
+                    .push("differs from generated output (hand-edited?)".to_string());
+            }
+        }
+
+        for collection in lockfile
+            .iter()
+            .flat_map(|lockfile| lockfile.collections.keys())
+        {
+            let module_name = collection.replace('-', "_");
+            if !module_files.iter().any(|(name, _)| *name == module_name) {
+                problems
+                    .entry(LOCKFILE_NAME.to_string())
+                    .or_default()
+                    .push(format!("records missing collection {}", collection));
+            }
+        }
+
+        for full_name in unseen_locked {
+            problems
+                .entry(LOCKFILE_NAME.to_string())
+                .or_default()
+                .push(format!("records missing icon {}", full_name));
+        }
+
+        for module_name in declared_modules.keys() {
+            if !module_files.iter().any(|(name, _)| name == module_name) {
+                problems
+                    .entry("mod.rs".to_string())
+                    .or_default()
+                    .push(format!("declares missing module {}", module_name));
+            }
+        }
+
+        Ok(problems)
     }
 
     /// Update a collection file (e.g., mdi.rs) with new icons
@@ -271,15 +748,18 @@ impl Generator {
         let content =
             self.generate_collection_file(collection, &existing_icons, collection_info)?;
 
-        // Write to file
-        fs::write(&file_path, content)
-            .context(format!("Failed to write collection file {:?}", file_path))?;
-
-        println!(
-            "✓ Updated {}.rs with {} icon(s)",
-            module_name,
-            new_icons.len()
-        );
+        // Write to file (only if the content changed, to avoid noisy diffs and recompiles)
+        if write_if_changed(&file_path, &content)
+            .context(format!("Failed to write collection file {:?}", file_path))?
+        {
+            println!(
+                "✓ Updated {}.rs with {} icon(s)",
+                module_name,
+                new_icons.len()
+            );
+        } else {
+            println!("✓ {}.rs is unchanged", module_name);
+        }
 
         Ok(())
     }
@@ -336,21 +816,20 @@ impl Generator {
         while j < lines.len() {
             let line = lines[j].trim();
 
-            if line.contains("name:") {
+            // Match field names at the start of the line only, the body may contain anything
+            if line.starts_with("name:") {
                 full_icon_name = extract_string_value(line);
-            } else if line.contains("body:") {
+            } else if line.starts_with("body:") {
                 // Body might span multiple lines in raw string
                 body = extract_raw_string_value(lines, &mut j);
-            } else if line.contains("view_box:") {
+            } else if line.starts_with("view_box:") {
                 view_box = extract_string_value(line);
-            } else if line.contains("width:") {
+            } else if line.starts_with("width:") {
                 width = extract_string_value(line);
-            } else if line.contains("height:") {
+            } else if line.starts_with("height:") {
                 height = extract_string_value(line);
-            }
-
-            // End of struct
-            if line.contains("};") {
+            } else if line.starts_with("};") {
+                // End of struct
                 break;
             }
 
@@ -380,12 +859,8 @@ impl Generator {
         icons: &BTreeMap<String, IconConst>,
         collection_info: Option<&IconifyCollectionInfo>,
     ) -> Result<String> {
-        let mut content = String::from("/// Auto-generated by dioxus-iconify - DO NOT EDIT\n");
-
-        // Add generation timestamp
-        let now = chrono::Utc::now();
-        content.push_str(&format!("/// Generated: {}\n", now.to_rfc3339()));
-
+        // No generation timestamp: the output must be byte-stable to avoid churn
+        let mut content = format!("{}\n", GENERATED_MARKER);
         content.push_str(&format!("/// Collection: {}\n", collection));
         content.push_str("/// This is a partial import from Iconify\n");
         content.push_str(&format!(
@@ -433,24 +908,76 @@ impl Generator {
 
         // Regenerate mod.rs if we have new modules
         if needs_update {
-            let mut new_content = MOD_RS_TEMPLATE.to_string();
-            new_content.push('\n');
+            write_if_changed(&mod_rs_path, &render_mod_rs(&existing_modules))
+                .context("Failed to update mod.rs")?;
+        }
 
-            // Add module declarations in alphabetical order
-            let mut sorted_modules: Vec<_> = existing_modules.iter().collect();
-            sorted_modules.sort_by_key(|(name, _)| *name);
+        Ok(())
+    }
 
-            for (module, visibility) in sorted_modules {
-                new_content.push_str(&format!("{}mod {};\n", visibility, module));
-            }
+    /// Remove module declarations from mod.rs, preserving the visibility of the others
+    fn remove_from_mod_rs(&self, modules: &[String]) -> Result<()> {
+        let mod_rs_path = self.icons_dir.join("mod.rs");
 
-            fs::write(&mod_rs_path, new_content).context("Failed to update mod.rs")?;
+        if !mod_rs_path.exists() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&mod_rs_path).context("Failed to read mod.rs")?;
+        let mut existing_modules = extract_module_declarations(&content);
+
+        let count_before = existing_modules.len();
+        existing_modules.retain(|name, _| !modules.contains(name));
+
+        if existing_modules.len() != count_before {
+            write_if_changed(&mod_rs_path, &render_mod_rs(&existing_modules))
+                .context("Failed to update mod.rs")?;
         }
 
         Ok(())
     }
 }
 
+/// Write a file only if its content differs, so unchanged files keep their mtime
+/// Returns true if the file was written
+pub fn write_if_changed(path: &Path, content: &str) -> Result<bool> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+
+    fs::write(path, content).context(format!("Failed to write {:?}", path))?;
+    Ok(true)
+}
+
+/// Check whether a collection file was generated by this tool
+fn is_generated_file(path: &Path) -> Result<bool> {
+    let content = fs::read_to_string(path).context(format!("Failed to read file {:?}", path))?;
+    Ok(content.starts_with(GENERATED_MARKER))
+}
+
+/// Render mod.rs content from the template and module declarations (sorted alphabetically)
+fn render_mod_rs(modules: &HashMap<String, String>) -> String {
+    let mut content = MOD_RS_TEMPLATE.to_string();
+    content.push('\n');
+
+    let mut sorted_modules: Vec<_> = modules.iter().collect();
+    sorted_modules.sort_by_key(|(name, _)| *name);
+
+    for (module, visibility) in sorted_modules {
+        content.push_str(&format!("{}mod {};\n", visibility, module));
+    }
+
+    content
+}
+
+/// Collection of the icons of a collection file (from their full names)
+fn collection_of(icons: &BTreeMap<String, IconConst>) -> Option<String> {
+    icons
+        .values()
+        .find_map(|icon| IconIdentifier::parse(&icon.full_icon_name).ok())
+        .map(|identifier| identifier.collection)
+}
+
 /// Extract module declarations from mod.rs content, preserving their visibility modifiers
 /// Returns a HashMap where keys are module names and values are visibility prefixes
 /// (e.g., "pub ", "pub(crate) ", "" for private modules)
@@ -562,11 +1089,72 @@ fn format_collection_info_comment(info: &IconifyCollectionInfo) -> String {
         lines.push(format!("/// height: {}", height));
     }
 
+    if let Some(version) = &info.version {
+        lines.push(format!("/// version: {}", version));
+    }
+
     lines.push("/// ```".to_string());
 
     lines.join("\n") + "\n"
 }
 
+/// Parse the collection info back from the YAML comment block of a collection file header
+/// (the reverse of `format_collection_info_comment`)
+fn parse_collection_info_comment(content: &str) -> Option<IconifyCollectionInfo> {
+    use crate::api::{IconifyAuthor, IconifyLicense};
+
+    let mut lines = content
+        .lines()
+        .take_while(|line| !line.starts_with("use "))
+        .skip_while(|line| *line != "/// ```yaml")
+        .peekable();
+    lines.next()?;
+
+    let mut info = IconifyCollectionInfo::default();
+    while let Some(line) = lines.next() {
+        let Some((key, value)) = line.strip_prefix("/// ").and_then(|l| l.split_once(':')) else {
+            break;
+        };
+        let value = value.trim();
+
+        // Fields of a detailed author or license, on the following indented lines
+        let mut fields = HashMap::new();
+        while let Some((field, field_value)) = lines
+            .next_if(|line| line.starts_with("///   "))
+            .and_then(|line| line[6..].split_once(':'))
+        {
+            fields.insert(field, field_value.trim().to_string());
+        }
+
+        match key {
+            "name" => info.name = Some(value.to_string()),
+            "author" if value.is_empty() => {
+                info.author = Some(IconifyAuthor::Detailed {
+                    name: fields.remove("name"),
+                    url: fields.remove("url"),
+                });
+            }
+            "author" => info.author = Some(IconifyAuthor::Simple(value.to_string())),
+            "license" if value.is_empty() => {
+                info.license = Some(IconifyLicense::Detailed {
+                    title: fields.remove("title"),
+                    spdx: fields.remove("spdx"),
+                    url: fields.remove("url"),
+                });
+            }
+            "license" => info.license = Some(IconifyLicense::Simple(value.to_string())),
+            "total" => info.total = value.parse().ok(),
+            "category" => info.category = Some(value.to_string()),
+            "palette" => info.palette = value.parse().ok(),
+            "height" => info.height = value.parse().ok(),
+            "version" => info.version = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
 /// Extract a string value from a line like `name: "value",`
 fn extract_string_value(line: &str) -> String {
     if let Some(start) = line.find('"')
@@ -578,44 +1166,61 @@ fn extract_string_value(line: &str) -> String {
     String::new()
 }
 
-/// Extract a raw string value that might span multiple lines
+/// Extract a raw string value (`r#"..."#`, with any number of '#') that might span multiple lines
 fn extract_raw_string_value(lines: &[&str], index: &mut usize) -> String {
     let line = lines[*index];
 
-    // Look for r#"..."#
-    if let Some(start) = line.find("r#\"") {
-        let start_pos = start + 3;
+    let Some(value_start) = line.find(':').map(|i| i + 1) else {
+        return String::new();
+    };
+    let value = line[value_start..].trim_start();
 
-        // Check if it ends on the same line
-        if let Some(end) = line[start_pos..].find("\"#") {
-            return line[start_pos..start_pos + end].to_string();
-        }
+    // Look for r#"...
+    let Some(after_r) = value.strip_prefix('r') else {
+        return String::new();
+    };
+    let hashes = &after_r[..after_r.len() - after_r.trim_start_matches('#').len()];
+    let Some(rest) = after_r[hashes.len()..].strip_prefix('"') else {
+        return String::new();
+    };
+    let terminator = format!("\"{}", hashes);
 
-        // Multi-line: collect until we find "#
-        let mut result = line[start_pos..].to_string();
-        *index += 1;
+    // Check if it ends on the same line
+    if let Some(end) = rest.find(&terminator) {
+        return rest[..end].to_string();
+    }
 
-        while *index < lines.len() {
-            let next_line = lines[*index];
-            if let Some(end) = next_line.find("\"#") {
-                result.push_str(&next_line[..end]);
-                break;
-            }
-            result.push_str(next_line);
-            result.push('\n');
-            *index += 1;
+    // Multi-line: collect until we find the terminator
+    let mut result = rest.to_string();
+    *index += 1;
+
+    while *index < lines.len() {
+        let next_line = lines[*index];
+        result.push('\n');
+        if let Some(end) = next_line.find(&terminator) {
+            result.push_str(&next_line[..end]);
+            break;
         }
+        result.push_str(next_line);
+        *index += 1;
+    }
 
-        result
-    } else {
-        String::new()
+    result
+}
+
+/// Build the '#' delimiter needed to embed `body` in a raw string literal
+fn raw_string_hashes(body: &str) -> String {
+    let mut hashes = String::from("#");
+    while body.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
     }
+    hashes
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::IconifyIcon;
+    use crate::api::{IconOrigin, IconifyIcon};
     use tempfile::TempDir;
 
     #[test]
@@ -641,16 +1246,22 @@ mod tests {
         // Add some test icons
         let test_icon1 = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let test_icon2 = IconifyIcon {
             body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier1 = IconIdentifier::parse("mdi:home")?;
@@ -707,9 +1318,12 @@ mod tests {
         // Add some test icons
         let test_icon = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier1 = IconIdentifier::parse("mdi:home")?;
@@ -755,9 +1369,12 @@ mod tests {
         // Add some test icons
         let test_icon = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier1 = IconIdentifier::parse("mdi:home")?;
@@ -836,9 +1453,12 @@ pub mod mdi;
         // Create test icon
         let test_icon = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier = IconIdentifier::parse("mdi:home")?;
@@ -862,6 +1482,7 @@ pub mod mdi;
                 category: Some("General".to_string()),
                 palette: Some(false),
                 height: Some(24),
+                version: Some("7.4.47".to_string()),
             },
         );
 
@@ -874,8 +1495,8 @@ pub mod mdi;
 
         // Verify generation metadata
         assert!(
-            content.contains("/// Generated:"),
-            "Should include generation timestamp"
+            !content.contains("/// Generated:"),
+            "Should not include generation timestamp"
         );
         assert!(
             content.contains("/// Collection: mdi"),
@@ -943,9 +1564,12 @@ pub mod mdi;
         // Add initial icons
         let test_icon = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier1 = IconIdentifier::parse("mdi:home")?;
@@ -1112,9 +1736,12 @@ pub(crate) mod app;
         // Now add some generated icons
         let test_icon = IconifyIcon {
             body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
-            width: Some(24),
-            height: Some(24),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
             view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
         };
 
         let identifier1 = IconIdentifier::parse("mdi:home")?;
@@ -1189,4 +1816,595 @@ pub(crate) mod app;
 
         Ok(())
     }
+
+    #[test]
+    fn test_remove_icons() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+
+        generator.add_icons(
+            &[
+                (IconIdentifier::parse("mdi:home")?, test_icon.clone()),
+                (IconIdentifier::parse("mdi:settings")?, test_icon.clone()),
+                (
+                    IconIdentifier::parse("heroicons:arrow-left")?,
+                    test_icon.clone(),
+                ),
+                (IconIdentifier::parse("lucide:star")?, test_icon.clone()),
+            ],
+            &HashMap::new(),
+        )?;
+
+        // Make heroicons private to check visibility is preserved
+        let mod_rs_path = icons_dir.join("mod.rs");
+        let mod_content = fs::read_to_string(&mod_rs_path)?;
+        fs::write(
+            &mod_rs_path,
+            mod_content.replace("pub mod heroicons;", "mod heroicons;"),
+        )?;
+
+        let removed = generator.remove_icons(&[
+            IconIdentifier::parse("mdi:home")?,
+            IconIdentifier::parse("lucide:*")?,
+            IconIdentifier::parse("heroicons:unknown")?,
+        ])?;
+
+        assert_eq!(removed.len(), 2, "Should remove mdi:home and lucide:star");
+        assert!(removed.contains(&"mdi:home".to_string()));
+        assert!(removed.contains(&"lucide:star".to_string()));
+
+        // mdi.rs keeps the other icon
+        let mdi_content = fs::read_to_string(icons_dir.join("mdi.rs"))?;
+        assert!(!mdi_content.contains("pub const Home:"));
+        assert!(mdi_content.contains("pub const Settings:"));
+        assert!(mdi_content.contains("/// Collection: mdi"));
+
+        // lucide.rs is deleted and its module dropped
+        assert!(!icons_dir.join("lucide.rs").exists());
+        let mod_after = fs::read_to_string(&mod_rs_path)?;
+        assert!(!mod_after.contains("mod lucide;"));
+        assert!(mod_after.lines().any(|l| l.trim() == "mod heroicons;"));
+        assert!(mod_after.lines().any(|l| l.trim() == "pub mod mdi;"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_generated_icon_identifiers_ignores_user_modules() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:home")?, test_icon)],
+            &HashMap::new(),
+        )?;
+
+        fs::write(
+            icons_dir.join("app.rs"),
+            r##"/// Custom user-defined icons
+use super::IconData;
+
+#[allow(non_upper_case_globals)]
+pub const CustomLogo: IconData = IconData {
+    name: "app:custom-logo",
+    body: r#"<rect width="100" height="100" fill="blue"/>"#,
+    view_box: "0 0 100 100",
+    width: "100",
+    height: "100",
+};
+"##,
+        )?;
+
+        assert_eq!(generator.get_all_icon_identifiers()?.len(), 2);
+        assert_eq!(
+            generator.get_generated_icon_identifiers()?,
+            vec!["mdi:home".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_up_to_date() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        generator.add_icons(
+            &[
+                (IconIdentifier::parse("mdi:home")?, test_icon.clone()),
+                (IconIdentifier::parse("heroicons:star")?, test_icon),
+            ],
+            &HashMap::new(),
+        )?;
+
+        let problems = generator.check()?;
+        assert!(problems.is_empty(), "Unexpected problems: {:?}", problems);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_reports_out_of_date_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        generator.add_icons(
+            &[
+                (IconIdentifier::parse("mdi:home")?, test_icon.clone()),
+                (IconIdentifier::parse("heroicons:star")?, test_icon),
+            ],
+            &HashMap::new(),
+        )?;
+
+        // Hand-edit a collection file
+        let mdi_path = icons_dir.join("mdi.rs");
+        let mdi_content = fs::read_to_string(&mdi_path)?;
+        fs::write(
+            &mdi_path,
+            mdi_content.replace("pub const Home:", "pub const House:") + "\n// my change\n",
+        )?;
+
+        // Outdated template and a declared module without file
+        let mod_rs_path = icons_dir.join("mod.rs");
+        let mod_content = fs::read_to_string(&mod_rs_path)?;
+        fs::write(
+            &mod_rs_path,
+            mod_content.replace("SvgAttributes", "GlobalAttributes") + "pub mod lucide;\n",
+        )?;
+
+        let problems = generator.check()?;
+
+        assert!(!problems.contains_key("heroicons.rs"));
+        let mdi_problems = problems.get("mdi.rs").expect("mdi.rs should be reported");
+        assert!(
+            mdi_problems
+                .iter()
+                .any(|p| p.contains("should be named Home"))
+        );
+        assert!(mdi_problems.iter().any(|p| p.contains("differs")));
+        let mod_problems = problems.get("mod.rs").expect("mod.rs should be reported");
+        assert!(mod_problems.iter().any(|p| p.contains("template")));
+        assert!(mod_problems.iter().any(|p| p.contains("lucide")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_uses_recorded_collection_info() -> Result<()> {
+        use crate::api::IconifyCollectionInfo;
+
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        let info = IconifyCollectionInfo {
+            name: Some("Material Design Icons".to_string()),
+            author: None,
+            license: None,
+            total: Some(7000),
+            category: None,
+            palette: None,
+            height: None,
+            version: Some("7.4.47".to_string()),
+        };
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:home")?, test_icon.clone())],
+            &HashMap::from([("mdi".to_string(), info.clone())]),
+        )?;
+        // Adding without collection info keeps the recorded one
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:star")?, test_icon)],
+            &HashMap::new(),
+        )?;
+        let mdi_path = icons_dir.join("mdi.rs");
+        let content = fs::read_to_string(&mdi_path)?;
+        assert!(content.contains("total: 7000"));
+        assert!(generator.check()?.is_empty());
+
+        // A hand-edited header is reported
+        fs::write(&mdi_path, content.replace("total: 7000", "total: 7001"))?;
+        let problems = generator.check()?;
+        let mdi_problems = problems.get("mdi.rs").expect("mdi.rs should be reported");
+        assert!(mdi_problems.iter().any(|p| p.contains("differs")));
+
+        // Version 1 lockfiles recover the collection info from the headers
+        fs::write(&mdi_path, content)?;
+        let lockfile_path = icons_dir.join(LOCKFILE_NAME);
+        let mut lockfile = generator.lockfile()?;
+        lockfile.version = 1;
+        lockfile.collections.clear();
+        lockfile.save(&lockfile_path)?;
+        let lockfile = generator.lockfile()?;
+        assert_eq!(lockfile.version, LOCKFILE_VERSION);
+        assert_eq!(lockfile.collections.get("mdi"), Some(&info));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_body_with_field_names_and_raw_string_delimiters() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let tricky_body = "<style>.a{fill:red};</style>\n<text>name: \"#x\" height: 2</text>";
+        let tricky_icon = IconifyIcon {
+            body: tricky_body.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:tricky")?, tricky_icon.clone())],
+            &HashMap::new(),
+        )?;
+
+        // Adding another icon re-parses the existing ones
+        let other_icon = IconifyIcon {
+            body: "<path/>".to_string(),
+            ..tricky_icon
+        };
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:other")?, other_icon)],
+            &HashMap::new(),
+        )?;
+
+        let icons = generator.parse_collection_file(&icons_dir.join("mdi.rs"))?;
+        let tricky = icons.get("Tricky").expect("Tricky should be parsed");
+        assert_eq!(tricky.full_icon_name, "mdi:tricky");
+        assert_eq!(tricky.body, tricky_body);
+        assert_eq!(tricky.height, "24");
+        assert!(generator.check()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offsets_and_fractional_dimensions() -> Result<()> {
+        let identifier = IconIdentifier::parse("custom:offset")?;
+        let icon = IconifyIcon {
+            body: "<path/>".to_string(),
+            left: Some(-2.0),
+            top: Some(0.5),
+            width: Some(20.5),
+            height: Some(21.0),
+            view_box: None,
+            origin: IconOrigin::default(),
+        };
+
+        let icon_const = IconConst::from_api_icon(&identifier, &icon);
+        assert_eq!(icon_const.view_box, "-2 0.5 20.5 21");
+        assert_eq!(icon_const.width, "20.5");
+        assert_eq!(icon_const.height, "21");
+
+        let defaults = IconConst::from_api_icon(
+            &identifier,
+            &IconifyIcon {
+                left: None,
+                top: None,
+                width: None,
+                height: None,
+                ..icon
+            },
+        );
+        assert_eq!(defaults.view_box, "0 0 24 24");
+        assert_eq!(defaults.width, "24");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_iconify_json() -> Result<()> {
+        use crate::api::{IconifyAuthor, IconifyLicense};
+
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let icon = |body: &str, view_box: &str| IconifyIcon {
+            body: body.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some(view_box.to_string()),
+            origin: IconOrigin::default(),
+        };
+        let info = IconifyCollectionInfo {
+            name: Some("Material Design Icons".to_string()),
+            author: Some(IconifyAuthor::Detailed {
+                name: Some("Pictogrammers".to_string()),
+                url: Some("https://pictogrammers.com".to_string()),
+            }),
+            license: Some(IconifyLicense::Simple("Apache 2.0".to_string())),
+            height: Some(24),
+            palette: Some(false),
+            version: Some("7.4.47".to_string()),
+            ..Default::default()
+        };
+
+        generator.add_icons(
+            &[
+                (
+                    IconIdentifier::parse("mdi:home")?,
+                    icon("<path/>", "0 0 24 24"),
+                ),
+                (
+                    IconIdentifier::parse("my-icons:logo")?,
+                    icon("<rect/>", "-2 0.5 20.5 21"),
+                ),
+            ],
+            &HashMap::from([("mdi".to_string(), info.clone())]),
+        )?;
+
+        let collections = generator.export_iconify_json()?;
+        assert_eq!(collections.keys().collect::<Vec<_>>(), ["mdi", "my-icons"]);
+
+        let mdi = &collections["mdi"];
+        assert_eq!(mdi.prefix, "mdi");
+        assert_eq!(
+            serde_json::to_value(&mdi.info)?,
+            serde_json::to_value(&info)?
+        );
+        assert_eq!(mdi.icons["home"].body, "<path/>");
+
+        let my_icons = &collections["my-icons"];
+        assert!(my_icons.info.is_none());
+        let exported = serde_json::to_value(my_icons)?;
+        assert_eq!(
+            exported["icons"]["logo"],
+            serde_json::json!({ "body": "<rect/>", "left": -2.0, "top": 0.5, "width": 20.5, "height": 21.0 })
+        );
+
+        // Exported collections resolve to the same icons
+        let resolved = my_icons.resolve("logo")?.unwrap();
+        assert_eq!(resolved.effective_view_box(), "-2 0.5 20.5 21");
+
+        // Offsets and fractional sizes without viewBox round trip through the viewBox
+        generator.add_icons(
+            &[(
+                IconIdentifier::parse("my-icons:offset")?,
+                IconifyIcon {
+                    left: Some(-2.25),
+                    top: Some(-0.5),
+                    width: Some(20.5),
+                    height: Some(20.75),
+                    view_box: None,
+                    ..icon("<circle/>", "")
+                },
+            )],
+            &HashMap::new(),
+        )?;
+        let collections = generator.export_iconify_json()?;
+        let exported = serde_json::to_value(&collections["my-icons"])?;
+        assert_eq!(
+            exported["icons"]["offset"],
+            serde_json::json!({ "body": "<circle/>", "left": -2.25, "top": -0.5, "width": 20.5, "height": 20.75 })
+        );
+        let resolved = collections["my-icons"].resolve("offset")?.unwrap();
+        assert_eq!(resolved.left, Some(-2.25));
+        assert_eq!(resolved.top, Some(-0.5));
+        assert_eq!(resolved.width, Some(20.5));
+        assert_eq!(resolved.height, Some(20.75));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lockfile_records_icons() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let api_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::Iconify {
+                last_modified: Some(1700000000),
+            },
+        };
+        let svg_icon = IconifyIcon {
+            origin: IconOrigin::Svg {
+                path: temp_dir.path().join("assets/logo.svg"),
+                symbol: None,
+                optimize: None,
+                monochrome: None,
+            },
+            ..api_icon.clone()
+        };
+
+        let mut collection_info = HashMap::new();
+        collection_info.insert(
+            "mdi".to_string(),
+            IconifyCollectionInfo {
+                name: None,
+                author: None,
+                license: None,
+                height: None,
+                category: None,
+                palette: None,
+                total: None,
+                version: Some("7.4.47".to_string()),
+            },
+        );
+
+        generator.add_icons(
+            &[
+                (IconIdentifier::parse("mdi:home")?, api_icon.clone()),
+                (IconIdentifier::parse("assets:logo")?, svg_icon),
+            ],
+            &collection_info,
+        )?;
+
+        // Local paths are recorded relative to the icons directory
+        let lockfile_path = icons_dir.join(LOCKFILE_NAME);
+        assert!(fs::read_to_string(&lockfile_path)?.contains("path = \"../assets/logo.svg\""));
+        let lockfile = Lockfile::load(&lockfile_path)?.expect("lockfile");
+        let home = lockfile.icons.get("mdi:home").expect("mdi:home locked");
+        assert_eq!(home.origin, api_icon.origin);
+        assert_eq!(home.collection_version, Some("7.4.47".to_string()));
+        assert_eq!(home.hash, hash_body(&api_icon.body));
+        let logo = lockfile
+            .icons
+            .get("assets:logo")
+            .expect("assets:logo locked");
+        assert_eq!(
+            logo.origin,
+            IconOrigin::Svg {
+                path: temp_dir.path().join("assets/logo.svg"),
+                symbol: None,
+                optimize: None,
+                monochrome: None,
+            }
+        );
+
+        // A hand-edited body is detected
+        let mdi_path = icons_dir.join("mdi.rs");
+        let content = fs::read_to_string(&mdi_path)?;
+        fs::write(&mdi_path, content.replace("r=\"10\"", "r=\"11\""))?;
+        let problems = generator.check()?;
+        assert!(
+            problems
+                .get("mdi.rs")
+                .is_some_and(|p| p.iter().any(|p| p.contains("differs from icons.lock")))
+        );
+
+        // Removing an icon drops it from the lockfile
+        generator.remove_icons(&[IconIdentifier::parse("assets:*")?])?;
+        let lockfile = Lockfile::load(&icons_dir.join(LOCKFILE_NAME))?.expect("lockfile");
+        assert!(!lockfile.icons.contains_key("assets:logo"));
+        assert!(lockfile.icons.contains_key("mdi:home"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lockfile_bootstrap_from_generated_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        generator.add_icons(
+            &[(IconIdentifier::parse("mdi:home")?, test_icon)],
+            &HashMap::new(),
+        )?;
+
+        // Simulate a directory generated by a version without lockfile
+        fs::remove_file(icons_dir.join(LOCKFILE_NAME))?;
+
+        assert_eq!(generator.get_all_icon_identifiers()?, vec!["mdi:home"]);
+        assert!(generator.check()?.contains_key(LOCKFILE_NAME));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_regeneration_is_byte_stable() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let icons_dir = temp_dir.path().join("icons");
+        let generator = Generator::new(icons_dir.clone());
+
+        let test_icon = IconifyIcon {
+            body: r#"<circle cx="12" cy="12" r="10"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        let icons = [
+            (IconIdentifier::parse("mdi:home")?, test_icon.clone()),
+            (IconIdentifier::parse("heroicons:star")?, test_icon),
+        ];
+        generator.add_icons(&icons, &HashMap::new())?;
+
+        let files = ["mdi.rs", "heroicons.rs", "mod.rs", LOCKFILE_NAME];
+        let snapshot = |dir: &Path| -> Result<Vec<(String, std::time::SystemTime)>> {
+            files
+                .iter()
+                .map(|f| {
+                    let path = dir.join(f);
+                    Ok((fs::read_to_string(&path)?, fs::metadata(&path)?.modified()?))
+                })
+                .collect()
+        };
+        let before = snapshot(&icons_dir)?;
+
+        // Make sure a rewrite would be visible in mtimes
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        generator.add_icons(&icons, &HashMap::new())?;
+        generator.regenerate_mod_rs()?;
+
+        assert_eq!(snapshot(&icons_dir)?, before);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,567 @@
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::{FetchedIcons, IconOrigin, IconifyCollectionInfo, IconifyIcon};
+
+/// Directories where npm installs IconifyJSON collections, searched automatically:
+/// the full `@iconify/json` package and the per-collection `@iconify-json/<prefix>` packages
+const NODE_MODULES_DIRS: &[&str] = &["node_modules/@iconify/json", "node_modules/@iconify-json"];
+
+/// Maximum length of an alias chain (same limit as the official Iconify utils)
+const MAX_ALIAS_DEPTH: usize = 36;
+
+/// A collection in the IconifyJSON format (full file, or partial API response)
+/// Based on IconifyJSON: https://iconify.design/docs/types/iconify-json.html
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IconifyJson {
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<IconifyCollectionInfo>,
+    #[serde(
+        default,
+        rename = "lastModified",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_modified: Option<u64>,
+    #[serde(default)]
+    pub icons: BTreeMap<String, IconifyJsonIcon>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, IconifyJsonAlias>,
+    /// Default properties of the icons
+    #[serde(flatten)]
+    pub defaults: IconProps,
+}
+
+/// Icon of an IconifyJSON collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconifyJsonIcon {
+    pub body: String,
+    #[serde(flatten)]
+    pub props: IconProps,
+}
+
+/// Alias of another icon (or alias) of an IconifyJSON collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconifyJsonAlias {
+    pub parent: String,
+    #[serde(flatten)]
+    pub props: IconProps,
+}
+
+/// Optional properties of icons and aliases, missing ones are inherited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IconProps {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate: Option<u32>,
+    #[serde(default, rename = "hFlip", skip_serializing_if = "Option::is_none")]
+    pub h_flip: Option<bool>,
+    #[serde(default, rename = "vFlip", skip_serializing_if = "Option::is_none")]
+    pub v_flip: Option<bool>,
+}
+
+impl IconProps {
+    /// Apply the properties of `other` (an alias): dimensions set in `other` override
+    /// these ones, transformations are combined
+    fn merge(&mut self, other: &IconProps) {
+        self.left = other.left.or(self.left);
+        self.top = other.top.or(self.top);
+        self.width = other.width.or(self.width);
+        self.height = other.height.or(self.height);
+        self.rotate = Some((self.rotate.unwrap_or(0) + other.rotate.unwrap_or(0)) % 4);
+        self.h_flip = Some(self.h_flip.unwrap_or(false) != other.h_flip.unwrap_or(false));
+        self.v_flip = Some(self.v_flip.unwrap_or(false) != other.v_flip.unwrap_or(false));
+    }
+}
+
+/// The viewBox of an icon
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ViewBox {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Bake the rotate/flip transformations into the body (as a `<g transform="...">`),
+/// swapping the viewBox dimensions for 90/270 degree rotations
+/// (same output as `iconToSVG` of the Iconify utils)
+fn apply_transformations(body: &str, view_box: &mut ViewBox, props: &IconProps) -> String {
+    let mut transformations = Vec::new();
+    let mut rotate = props.rotate.unwrap_or(0);
+
+    match (props.h_flip.unwrap_or(false), props.v_flip.unwrap_or(false)) {
+        // Flipping both ways is a half turn
+        (true, true) => rotate += 2,
+        (true, false) => {
+            transformations.push(format!(
+                "translate({} {})",
+                view_box.width + view_box.left,
+                0.0 - view_box.top
+            ));
+            transformations.push("scale(-1 1)".to_string());
+            view_box.left = 0.0;
+            view_box.top = 0.0;
+        }
+        (false, true) => {
+            transformations.push(format!(
+                "translate({} {})",
+                0.0 - view_box.left,
+                view_box.height + view_box.top
+            ));
+            transformations.push("scale(1 -1)".to_string());
+            view_box.left = 0.0;
+            view_box.top = 0.0;
+        }
+        (false, false) => {}
+    }
+
+    match rotate % 4 {
+        1 => {
+            let center = view_box.height / 2.0 + view_box.top;
+            transformations.insert(0, format!("rotate(90 {} {})", center, center));
+        }
+        2 => {
+            transformations.insert(
+                0,
+                format!(
+                    "rotate(180 {} {})",
+                    view_box.width / 2.0 + view_box.left,
+                    view_box.height / 2.0 + view_box.top
+                ),
+            );
+        }
+        3 => {
+            let center = view_box.width / 2.0 + view_box.left;
+            transformations.insert(0, format!("rotate(-90 {} {})", center, center));
+        }
+        _ => {}
+    }
+
+    if rotate % 2 == 1 {
+        std::mem::swap(&mut view_box.left, &mut view_box.top);
+        std::mem::swap(&mut view_box.width, &mut view_box.height);
+    }
+
+    if transformations.is_empty() {
+        body.to_string()
+    } else {
+        format!(
+            "<g transform=\"{}\">{}</g>",
+            transformations.join(" "),
+            body
+        )
+    }
+}
+
+impl IconifyJson {
+    /// Load a collection from an IconifyJSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+
+        serde_json::from_str(&content).context(format!(
+            "Failed to parse IconifyJSON collection {}",
+            path.display()
+        ))
+    }
+
+    /// Resolve an icon or an alias, returns `None` if the collection doesn't have it
+    pub fn resolve(&self, name: &str) -> Result<Option<IconifyIcon>> {
+        // Follow the alias chain up to the icon
+        let mut chain = Vec::new();
+        let mut current = name;
+        let icon = loop {
+            if let Some(icon) = self.icons.get(current) {
+                break icon;
+            }
+            let Some(alias) = self.aliases.get(current) else {
+                return Ok(None);
+            };
+            if chain.len() >= MAX_ALIAS_DEPTH {
+                return Err(anyhow!("Alias '{}' is too deeply nested", name));
+            }
+            chain.push(alias);
+            current = &alias.parent;
+        };
+
+        // Collection defaults, then icon properties, then aliases (outermost last)
+        let mut props = IconProps {
+            rotate: None,
+            h_flip: None,
+            v_flip: None,
+            ..self.defaults.clone()
+        };
+        props.merge(&icon.props);
+        for alias in chain.iter().rev() {
+            props.merge(&alias.props);
+        }
+
+        let mut view_box = ViewBox {
+            left: props.left.unwrap_or(0.0),
+            top: props.top.unwrap_or(0.0),
+            width: props.width.unwrap_or(16.0),
+            height: props.height.unwrap_or(16.0),
+        };
+        let body = apply_transformations(&icon.body, &mut view_box, &props);
+
+        Ok(Some(IconifyIcon {
+            body,
+            left: Some(view_box.left),
+            top: Some(view_box.top),
+            width: Some(view_box.width),
+            height: Some(view_box.height),
+            view_box: None,
+            origin: IconOrigin::Iconify {
+                last_modified: self.last_modified,
+            },
+        }))
+    }
+
+    /// Names of all the icons and aliases, sorted
+    pub fn icon_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .icons
+            .keys()
+            .chain(self.aliases.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Resolve several icons (`source` is where the collection comes from)
+    pub fn fetch_icons(&self, icon_names: &[String], source: &str) -> Result<FetchedIcons> {
+        let mut fetched = FetchedIcons {
+            source: source.to_string(),
+            ..Default::default()
+        };
+
+        for icon_name in icon_names {
+            match self.resolve(icon_name)? {
+                Some(icon) => {
+                    fetched.icons.insert(icon_name.clone(), icon);
+                }
+                None => fetched.not_found.push(icon_name.clone()),
+            }
+        }
+
+        Ok(fetched)
+    }
+}
+
+/// Local directories of IconifyJSON collection files, used instead of the API when
+/// they have the requested collection (e.g., on build machines without network)
+#[derive(Debug, Clone, Default)]
+pub struct IconSets {
+    dirs: Vec<PathBuf>,
+    /// Custom IconifyJSON files loaded so far, by prefix
+    files: RefCell<HashMap<String, PathBuf>>,
+}
+
+/// Split an input naming a custom IconifyJSON file: `./icons.json` (all its icons)
+/// or `./icons.json:logo` (one icon), returns `None` for other inputs
+pub fn split_file_input(input: &str) -> Option<(&Path, Option<&str>)> {
+    let is_json_file = |path: &str| path.ends_with(".json") && Path::new(path).is_file();
+    if is_json_file(input) {
+        return Some((Path::new(input), None));
+    }
+
+    let (path, name) = input.rsplit_once(':')?;
+    (is_json_file(path) && !name.is_empty()).then_some((Path::new(path), Some(name)))
+}
+
+impl IconSets {
+    /// Search `dirs` first, then the npm packages installed in `node_modules` (when present)
+    pub fn new(dirs: &[PathBuf]) -> Result<Self> {
+        for dir in dirs {
+            if !dir.is_dir() {
+                return Err(anyhow!(
+                    "Icon source directory not found: {}",
+                    dir.display()
+                ));
+            }
+        }
+
+        let mut dirs = dirs.to_vec();
+        dirs.extend(
+            NODE_MODULES_DIRS
+                .iter()
+                .map(PathBuf::from)
+                .filter(|dir| dir.is_dir()),
+        );
+
+        Ok(Self {
+            dirs,
+            ..Default::default()
+        })
+    }
+
+    /// Find the IconifyJSON file of a collection: a custom file loaded before, or a file of
+    /// the directories
+    ///
+    /// Supported layouts: `<dir>/<prefix>.json`, `<dir>/json/<prefix>.json` (`@iconify/json`)
+    /// and `<dir>/<prefix>/icons.json` (`@iconify-json/<prefix>`)
+    pub fn find(&self, prefix: &str) -> Option<PathBuf> {
+        if let Some(path) = self.files.borrow().get(prefix) {
+            return Some(path.clone());
+        }
+
+        self.dirs.iter().find_map(|dir| {
+            [
+                dir.join(format!("{}.json", prefix)),
+                dir.join("json").join(format!("{}.json", prefix)),
+                dir.join(prefix).join("icons.json"),
+            ]
+            .into_iter()
+            .find(|path| path.is_file())
+        })
+    }
+
+    /// Load the IconifyJSON collection, returns `None` if no local file has it
+    pub fn load(&self, prefix: &str) -> Result<Option<(PathBuf, IconifyJson)>> {
+        let Some(path) = self.find(prefix) else {
+            return Ok(None);
+        };
+
+        let collection = IconifyJson::load(&path)?;
+        if collection.prefix != prefix {
+            return Err(anyhow!(
+                "{} contains collection '{}' instead of '{}'",
+                path.display(),
+                collection.prefix,
+                prefix
+            ));
+        }
+
+        Ok(Some((path, collection)))
+    }
+
+    /// Load a custom IconifyJSON file, its collection is then found by its prefix
+    pub fn load_file(&self, path: &Path) -> Result<IconifyJson> {
+        let collection = IconifyJson::load(path)?;
+        if collection.prefix.is_empty() {
+            return Err(anyhow!("{} has no prefix", path.display()));
+        }
+
+        self.files
+            .borrow_mut()
+            .insert(collection.prefix.clone(), path.to_path_buf());
+        Ok(collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    const COLLECTION: &str = indoc! {r#"
+        {
+            "prefix": "test",
+            "lastModified": 1700000000,
+            "info": { "name": "Test Icons", "total": 4, "height": [24, 16] },
+            "width": 24,
+            "height": 24,
+            "icons": {
+                "home": { "body": "<path d=\"M1 1\"/>" },
+                "wide": { "body": "<rect/>", "width": 32, "left": -4 },
+                "arrow-left": { "body": "<path d=\"M2 2\"/>" },
+                "fractional": { "body": "<circle/>", "left": 0.5, "top": -1.25, "width": 20.5, "height": 21 }
+            },
+            "aliases": {
+                "house": { "parent": "home" },
+                "house-big": { "parent": "house", "width": 48, "height": 48 },
+                "arrow-right": { "parent": "arrow-left", "hFlip": true },
+                "arrow-down": { "parent": "arrow-right", "rotate": 1 },
+                "arrow-up": { "parent": "arrow-left", "vFlip": true },
+                "arrow-reversed": { "parent": "arrow-left", "hFlip": true, "vFlip": true },
+                "arrow-back": { "parent": "arrow-right", "hFlip": true },
+                "wide-rotated": { "parent": "wide", "rotate": 1 },
+                "loop": { "parent": "loop" }
+            }
+        }
+    "#};
+
+    #[rstest]
+    #[case("home", "0 0 24 24", 24.0, 24.0)]
+    #[case("wide", "-4 0 32 24", 32.0, 24.0)]
+    #[case("house", "0 0 24 24", 24.0, 24.0)]
+    #[case("house-big", "0 0 48 48", 48.0, 48.0)]
+    #[case("fractional", "0.5 -1.25 20.5 21", 20.5, 21.0)]
+    fn test_resolve(
+        #[case] name: &str,
+        #[case] view_box: &str,
+        #[case] width: f64,
+        #[case] height: f64,
+    ) -> Result<()> {
+        let collection: IconifyJson = serde_json::from_str(COLLECTION)?;
+        let icon = collection.resolve(name)?.unwrap();
+
+        assert_eq!(icon.effective_view_box(), view_box);
+        assert_eq!(icon.width, Some(width));
+        assert_eq!(icon.height, Some(height));
+        assert_eq!(
+            icon.origin,
+            IconOrigin::Iconify {
+                last_modified: Some(1700000000)
+            }
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        "arrow-right",
+        r#"<g transform="translate(24 0) scale(-1 1)"><path d="M2 2"/></g>"#,
+        "0 0 24 24"
+    )]
+    #[case(
+        "arrow-down",
+        r#"<g transform="rotate(90 12 12) translate(24 0) scale(-1 1)"><path d="M2 2"/></g>"#,
+        "0 0 24 24"
+    )]
+    #[case(
+        "arrow-up",
+        r#"<g transform="translate(0 24) scale(1 -1)"><path d="M2 2"/></g>"#,
+        "0 0 24 24"
+    )]
+    #[case(
+        "arrow-reversed",
+        r#"<g transform="rotate(180 12 12)"><path d="M2 2"/></g>"#,
+        "0 0 24 24"
+    )]
+    // Flipped twice along the chain
+    #[case("arrow-back", r#"<path d="M2 2"/>"#, "0 0 24 24")]
+    // Quarter turn of a non-square icon
+    #[case(
+        "wide-rotated",
+        r#"<g transform="rotate(90 12 12)"><rect/></g>"#,
+        "0 -4 24 32"
+    )]
+    fn test_resolve_transformations(
+        #[case] name: &str,
+        #[case] body: &str,
+        #[case] view_box: &str,
+    ) -> Result<()> {
+        let collection: IconifyJson = serde_json::from_str(COLLECTION)?;
+        let icon = collection.resolve(name)?.unwrap();
+
+        assert_eq!(icon.body, body);
+        assert_eq!(icon.effective_view_box(), view_box);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_errors() -> Result<()> {
+        let collection: IconifyJson = serde_json::from_str(COLLECTION)?;
+
+        assert!(collection.resolve("ghost")?.is_none());
+        assert!(collection.resolve("loop").is_err());
+
+        let info = collection.info.unwrap();
+        assert_eq!(info.name.as_deref(), Some("Test Icons"));
+        assert_eq!(info.height, Some(24));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_dimensions() -> Result<()> {
+        let collection: IconifyJson =
+            serde_json::from_str(r#"{"prefix":"x","icons":{"a":{"body":"<path/>"}}}"#)?;
+        let icon = collection.resolve("a")?.unwrap();
+
+        assert_eq!(icon.effective_view_box(), "0 0 16 16");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_collection_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let flat_dir = temp_dir.path().join("flat");
+        let package_dir = temp_dir.path().join("iconify-json");
+        fs::create_dir_all(flat_dir.join("json"))?;
+        fs::create_dir_all(package_dir.join("mdi"))?;
+        fs::write(flat_dir.join("test.json"), COLLECTION)?;
+        fs::write(flat_dir.join("json").join("lucide.json"), "{}")?;
+        fs::write(package_dir.join("mdi").join("icons.json"), "{}")?;
+
+        let icon_sets = IconSets::new(&[flat_dir.clone(), package_dir.clone()])?;
+
+        assert_eq!(icon_sets.find("test"), Some(flat_dir.join("test.json")));
+        assert_eq!(
+            icon_sets.find("lucide"),
+            Some(flat_dir.join("json").join("lucide.json"))
+        );
+        assert_eq!(
+            icon_sets.find("mdi"),
+            Some(package_dir.join("mdi").join("icons.json"))
+        );
+        assert_eq!(icon_sets.find("heroicons"), None);
+
+        let (path, collection) = icon_sets.load("test")?.unwrap();
+        let fetched = collection.fetch_icons(
+            &["home".to_string(), "ghost".to_string()],
+            &path.display().to_string(),
+        )?;
+        assert!(fetched.icons.contains_key("home"));
+        assert_eq!(fetched.not_found, vec!["ghost"]);
+        assert_eq!(fetched.source, path.display().to_string());
+
+        assert!(IconSets::new(&[temp_dir.path().join("missing")]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_custom_file() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("company-icons.json");
+        let no_prefix = temp_dir.path().join("no-prefix.json");
+        fs::write(&path, COLLECTION)?;
+        fs::write(&no_prefix, r#"{"icons":{}}"#)?;
+
+        let input = path.to_string_lossy().to_string();
+        assert_eq!(split_file_input(&input), Some((path.as_path(), None)));
+        assert_eq!(
+            split_file_input(&format!("{}:logo", input)),
+            Some((path.as_path(), Some("logo")))
+        );
+        assert_eq!(split_file_input(&format!("{}:", input)), None);
+        assert_eq!(split_file_input("mdi:home"), None);
+        assert_eq!(split_file_input("missing.json:logo"), None);
+
+        let icon_sets = IconSets::new(&[])?;
+        assert_eq!(icon_sets.find("test"), None);
+
+        let collection = icon_sets.load_file(&path)?;
+        assert_eq!(
+            collection.icon_names()[..3],
+            ["arrow-back", "arrow-down", "arrow-left"]
+        );
+        assert_eq!(icon_sets.find("test"), Some(path.clone()));
+        assert!(icon_sets.load("test")?.unwrap().1.info.is_some());
+
+        assert!(icon_sets.load_file(&no_prefix).is_err());
+
+        Ok(())
+    }
+}
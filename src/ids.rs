@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+use crate::naming::IconIdentifier;
+use crate::svg::{self, Node};
+
+/// Attributes holding a whitespace-separated list of IDs
+const ID_LIST_ATTRIBUTES: &[&str] = &["aria-labelledby", "aria-describedby"];
+
+/// Prefix of the IDs of an icon, unique per icon since identifiers can't contain `_`
+/// Example: "my-icons:logo" → "my-icons_logo_"
+pub fn id_prefix(identifier: &IconIdentifier) -> String {
+    format!("{}_{}_", identifier.collection, identifier.icon_name)
+}
+
+/// Prefix the IDs defined in a body, and the references to them (`url(#id)`, `href="#id"`,
+/// `xlink:href="#id"`, ARIA relationships and `#id` selectors of stylesheets), so that icons
+/// on the same page don't clash
+///
+/// IDs that already have the prefix are kept as-is, so that prefixing is idempotent.
+pub fn prefix_ids(body: &str, prefix: &str) -> Result<String> {
+    // Most bodies have no ID at all, don't reformat them
+    if !body.contains("id=") {
+        return Ok(body.to_string());
+    }
+
+    let wrapped = svg::wrap_body(body);
+    let doc = roxmltree::Document::parse(&wrapped).context("Failed to parse SVG body")?;
+    let mut nodes: Vec<Node> = doc
+        .root_element()
+        .children()
+        .filter_map(|node| Node::build(&node))
+        .collect();
+
+    let ids: HashSet<String> = doc
+        .descendants()
+        .filter_map(|node| node.attribute("id"))
+        .filter(|id| !id.starts_with(prefix))
+        .map(str::to_string)
+        .collect();
+    if ids.is_empty() {
+        return Ok(body.to_string());
+    }
+
+    let renamer = Renamer { prefix, ids };
+    renamer.nodes(&mut nodes);
+
+    let mut prefixed = String::new();
+    for node in &nodes {
+        node.write(&mut prefixed);
+    }
+
+    Ok(prefixed)
+}
+
+/// Renames the IDs of a body, and the references to them
+struct Renamer<'a> {
+    prefix: &'a str,
+    /// IDs defined in the body (references to other IDs are left as-is)
+    ids: HashSet<String>,
+}
+
+impl Renamer<'_> {
+    fn nodes(&self, nodes: &mut [Node]) {
+        for node in nodes {
+            let Node::Element(element) = node else {
+                continue;
+            };
+
+            for (name, value) in &mut element.attributes {
+                *value = match name.as_str() {
+                    "id" => self.id(value),
+                    "href" | "xlink:href" => match value.strip_prefix('#') {
+                        Some(id) => format!("#{}", self.id(id)),
+                        None => value.clone(),
+                    },
+                    name if ID_LIST_ATTRIBUTES.contains(&name) => value
+                        .split_whitespace()
+                        .map(|id| self.id(id))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    _ => self.urls(value),
+                };
+            }
+
+            if element.name == "style" {
+                for child in &mut element.children {
+                    if let Node::Text(css) = child {
+                        *css = self.stylesheet(css);
+                    }
+                }
+            }
+
+            self.nodes(&mut element.children);
+        }
+    }
+
+    /// The new name of an ID (unchanged if it isn't defined in the body)
+    fn id(&self, id: &str) -> String {
+        if self.ids.contains(id) {
+            format!("{}{}", self.prefix, id)
+        } else {
+            id.to_string()
+        }
+    }
+
+    /// Rename the `url(#id)` references of a value (e.g., `fill="url(#gradient)"`)
+    fn urls(&self, value: &str) -> String {
+        let mut renamed = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("url(") {
+            let (before, after) = rest.split_at(start + "url(".len());
+            renamed.push_str(before);
+            let end = after.find(')').unwrap_or(after.len());
+            match after[..end]
+                .trim_matches(['\'', '"', ' '])
+                .strip_prefix('#')
+            {
+                Some(id) => renamed.push_str(&format!("#{}", self.id(id))),
+                None => renamed.push_str(&after[..end]),
+            }
+            rest = &after[end..];
+        }
+        renamed.push_str(rest);
+        renamed
+    }
+
+    /// Rename the references of a stylesheet: `url(#id)` in declarations,
+    /// and `#id` in selectors
+    fn stylesheet(&self, css: &str) -> String {
+        let mut renamed = String::new();
+        let mut end = 0;
+        for block in svg::css_declaration_blocks(css) {
+            renamed.push_str(&self.selectors(&css[end..block.start]));
+            renamed.push_str(&self.urls(&css[block.clone()]));
+            end = block.end;
+        }
+        renamed.push_str(&self.selectors(&css[end..]));
+        renamed
+    }
+
+    /// Rename the `#id` selectors of a stylesheet (outside of declaration blocks)
+    fn selectors(&self, css: &str) -> String {
+        let mut renamed = String::new();
+        let mut rest = css;
+        while let Some(start) = rest.find('#') {
+            renamed.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(rest.len());
+            renamed.push_str(&self.id(&rest[..end]));
+            rest = &rest[end..];
+        }
+        renamed.push_str(rest);
+        renamed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_id_prefix() -> Result<()> {
+        assert_eq!(
+            id_prefix(&IconIdentifier::parse("my-icons:arrows-left")?),
+            "my-icons_arrows-left_"
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::no_ids(r#"<path d="M0 0"/>"#, r#"<path d="M0 0"/>"#)]
+    #[case::gradient(
+        r##"<defs><linearGradient id="a"><stop offset="0"/></linearGradient></defs><path fill="url(#a)" style="stroke: url('#a')" d="M0 0"/>"##,
+        r##"<defs><linearGradient id="logo_a"><stop offset="0"/></linearGradient></defs><path fill="url(#logo_a)" style="stroke: url(#logo_a)" d="M0 0"/>"##
+    )]
+    #[case::href(
+        r##"<defs><path id="dot" d="M0 0"/><linearGradient id="b" xlink:href="#a"/></defs><use xlink:href="#dot"/><use href="#dot"/>"##,
+        r##"<defs><path id="logo_dot" d="M0 0"/><linearGradient id="logo_b" xlink:href="#a"/></defs><use xlink:href="#logo_dot"/><use href="#logo_dot"/>"##
+    )]
+    #[case::aria(
+        r#"<title id="t">Logo</title><desc id="d">A logo</desc><g aria-labelledby="t d"><path d="M0 0"/></g>"#,
+        r#"<title id="logo_t">Logo</title><desc id="logo_d">A logo</desc><g aria-labelledby="logo_t logo_d"><path d="M0 0"/></g>"#
+    )]
+    #[case::stylesheet(
+        r##"<style>#c, .a > #c { fill: #c00; mask: url(#m) }</style><mask id="m"/><circle id="c" r="2"/>"##,
+        "<style><![CDATA[#logo_c, .a > #logo_c { fill: #c00; mask: url(#logo_m) }]]></style><mask id=\"logo_m\"/><circle id=\"logo_c\" r=\"2\"/>"
+    )]
+    fn test_prefix_ids(#[case] body: &str, #[case] expected: &str) -> Result<()> {
+        assert_eq!(prefix_ids(body, "logo_")?, expected);
+        // Prefixing is idempotent
+        assert_eq!(prefix_ids(expected, "logo_")?, expected);
+
+        Ok(())
+    }
+}
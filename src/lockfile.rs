@@ -0,0 +1,446 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::api::{DEFAULT_ICON_SIZE, IconOrigin, IconifyCollectionInfo, IconifyIcon};
+use crate::generator::write_if_changed;
+use crate::manifest::IconOptions;
+
+/// Lockfile name, written in the icons directory next to mod.rs
+pub const LOCKFILE_NAME: &str = "icons.lock";
+
+const LOCKFILE_HEADER: &str = "# Auto-generated by dioxus-iconify - DO NOT EDIT\n";
+
+/// Current lockfile format version
+/// (version 2 records the collection info, version 1 lockfiles are migrated on load)
+pub const LOCKFILE_VERSION: u32 = 2;
+
+/// Provenance and content hashes of every vendored icon (icons.lock)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    /// Info of the collections, keyed by collection (e.g., "mdi"), from which the headers
+    /// of the generated files are rebuilt
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub collections: BTreeMap<String, IconifyCollectionInfo>,
+    /// Locked icons keyed by full icon name (e.g., "mdi:home")
+    #[serde(default)]
+    pub icons: BTreeMap<String, LockedIcon>,
+}
+
+/// A single vendored icon as recorded in the lockfile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedIcon {
+    #[serde(flatten)]
+    pub origin: IconOrigin,
+    /// Version of the collection at import time (Iconify collections only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_version: Option<String>,
+    pub width: String,
+    pub height: String,
+    pub view_box: String,
+    /// SHA-256 of the icon body, formatted as "sha256:<hex>"
+    pub hash: String,
+    /// Options of the manifest applied by `sync`, which re-applies them when they change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<IconOptions>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            collections: BTreeMap::new(),
+            icons: BTreeMap::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Load a lockfile, returns `None` if it doesn't exist
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read lockfile {}", path.display()))?;
+        let mut lockfile: Self = toml::from_str(&content)
+            .context(format!("Failed to parse lockfile {}", path.display()))?;
+
+        // Local files are recorded relative to the lockfile directory
+        let dir = lockfile_dir(path)?;
+        for locked in lockfile.icons.values_mut() {
+            if let Some(origin_path) = locked.origin.path_mut() {
+                *origin_path = normalize_path(&dir.join(&*origin_path));
+            }
+        }
+
+        Ok(Some(lockfile))
+    }
+
+    /// Write the lockfile, with the paths of local files relative to its directory
+    /// (so that it stays valid wherever the project is checked out and the commands run)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let dir = lockfile_dir(path)?;
+        let mut lockfile = self.clone();
+        for locked in lockfile.icons.values_mut() {
+            if let Some(origin_path) = locked.origin.path_mut() {
+                *origin_path = relative_path(&std::path::absolute(&*origin_path)?, &dir);
+            }
+        }
+
+        let content = toml::to_string(&lockfile).context("Failed to serialize lockfile")?;
+
+        write_if_changed(path, &format!("{}{}", LOCKFILE_HEADER, content))
+            .context(format!("Failed to write lockfile {}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+impl LockedIcon {
+    pub fn new(icon: &IconifyIcon, collection_version: Option<String>) -> Self {
+        Self {
+            origin: icon.origin.clone(),
+            collection_version,
+            width: icon.width.unwrap_or(DEFAULT_ICON_SIZE).to_string(),
+            height: icon.height.unwrap_or(DEFAULT_ICON_SIZE).to_string(),
+            view_box: icon.effective_view_box(),
+            hash: hash_body(&icon.body),
+            options: None,
+        }
+    }
+
+    /// List what differs between the recorded icon and a newly fetched one
+    /// (empty when the icon is unchanged)
+    pub fn changes(&self, icon: &IconifyIcon) -> Vec<&'static str> {
+        let other = Self::new(icon, None);
+        let mut changes = Vec::new();
+
+        if self.hash != other.hash {
+            changes.push("body");
+        }
+        if self.view_box != other.view_box {
+            changes.push("viewBox");
+        }
+        if self.width != other.width || self.height != other.height {
+            changes.push("dimensions");
+        }
+        if let (
+            IconOrigin::Archive { archive_hash, .. },
+            IconOrigin::Archive {
+                archive_hash: other_hash,
+                ..
+            },
+        ) = (&self.origin, &other.origin)
+            && archive_hash != other_hash
+        {
+            changes.push("archive");
+        }
+        if origin_source(&self.origin) != origin_source(&other.origin) {
+            changes.push("origin");
+        }
+        if self.origin.optimize() != other.origin.optimize() {
+            changes.push("optimize");
+        }
+        if self.origin.monochrome() != other.origin.monochrome() {
+            changes.push("monochrome");
+        }
+
+        changes
+    }
+}
+
+/// Where an icon is read from, without what is compared on its own (archive hash,
+/// optimization preset, colour mode) or changes with every release (`lastModified`)
+fn origin_source(origin: &IconOrigin) -> IconOrigin {
+    match origin.clone() {
+        IconOrigin::Iconify { .. } => IconOrigin::Iconify {
+            last_modified: None,
+        },
+        IconOrigin::IconSet { path, .. } => IconOrigin::IconSet {
+            path,
+            last_modified: None,
+            monochrome: None,
+        },
+        IconOrigin::Svg { path, symbol, .. } => IconOrigin::Svg {
+            path,
+            symbol,
+            optimize: None,
+            monochrome: None,
+        },
+        IconOrigin::Archive { path, entry, .. } => IconOrigin::Archive {
+            path,
+            entry,
+            archive_hash: String::new(),
+            optimize: None,
+            monochrome: None,
+        },
+    }
+}
+
+/// Absolute directory of a lockfile
+fn lockfile_dir(path: &Path) -> Result<PathBuf> {
+    let path = std::path::absolute(path).context(format!(
+        "Failed to resolve lockfile path {}",
+        path.display()
+    ))?;
+    Ok(normalize_path(path.parent().unwrap_or(&path)))
+}
+
+/// Remove the `.` and `..` components of an absolute path
+/// Example: "/project/src/icons/../../assets/logo.svg" → "/project/assets/logo.svg"
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Path of `path` relative to the `base` directory (both absolute)
+/// Example: ("/project/assets/logo.svg", "/project/src/icons") → "../../assets/logo.svg"
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path = normalize_path(path);
+    let mut path_components = path.components().peekable();
+    let mut base_components = base.components().peekable();
+
+    // Paths on different drives (Windows) can't be relative
+    if path_components.peek() != base_components.peek() {
+        return path;
+    }
+    while path_components.peek().is_some() && path_components.peek() == base_components.peek() {
+        path_components.next();
+        base_components.next();
+    }
+
+    base_components
+        .map(|_| Component::ParentDir)
+        .chain(path_components)
+        .collect()
+}
+
+/// Hash an icon body, formatted as "sha256:<hex>"
+pub fn hash_body(body: &str) -> String {
+    hash_bytes(body.as_bytes())
+}
+
+/// Hash some content (e.g., an archive), formatted as "sha256:<hex>"
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::Preset;
+    use rstest::rstest;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hash_body() {
+        assert_eq!(
+            hash_body(""),
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_ne!(hash_body("<path/>"), hash_body("<path />"));
+    }
+
+    #[test]
+    fn test_changes() {
+        let icon = IconifyIcon {
+            body: "<path/>".to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+        let locked = LockedIcon::new(&icon, None);
+
+        assert!(locked.changes(&icon).is_empty());
+
+        let changed = IconifyIcon {
+            body: "<circle/>".to_string(),
+            left: None,
+            top: None,
+            width: Some(32.0),
+            ..icon.clone()
+        };
+        assert_eq!(locked.changes(&changed), vec!["body", "dimensions"]);
+
+        let changed = IconifyIcon {
+            view_box: Some("0 0 32 32".to_string()),
+            ..icon.clone()
+        };
+        assert_eq!(locked.changes(&changed), vec!["viewBox"]);
+
+        // Icons of archives also change with the archive
+        let archive_origin = |archive_hash: &str| IconOrigin::Archive {
+            path: PathBuf::from("icons.tgz"),
+            entry: "package/icons/home.svg".to_string(),
+            archive_hash: archive_hash.to_string(),
+            optimize: None,
+            monochrome: None,
+        };
+        let archived = IconifyIcon {
+            origin: archive_origin("sha256:1"),
+            ..icon
+        };
+        let locked = LockedIcon::new(&archived, None);
+        assert!(locked.changes(&archived).is_empty());
+        let changed = IconifyIcon {
+            origin: archive_origin("sha256:2"),
+            ..archived.clone()
+        };
+        assert_eq!(locked.changes(&changed), vec!["archive"]);
+        let optimized = IconifyIcon {
+            origin: IconOrigin::Archive {
+                path: PathBuf::from("icons.tgz"),
+                entry: "package/icons/home.svg".to_string(),
+                archive_hash: "sha256:1".to_string(),
+                optimize: Some(Preset::Safe),
+                monochrome: None,
+            },
+            ..archived
+        };
+        assert_eq!(locked.changes(&optimized), vec!["optimize"]);
+
+        // An icon read from another file or symbol changes origin
+        let moved = IconifyIcon {
+            origin: IconOrigin::Svg {
+                path: PathBuf::from("logo.svg"),
+                symbol: Some("logo".to_string()),
+                optimize: None,
+                monochrome: None,
+            },
+            ..optimized
+        };
+        assert_eq!(locked.changes(&moved), vec!["origin"]);
+        let locked = LockedIcon::new(&moved, None);
+        let other_symbol = IconifyIcon {
+            origin: IconOrigin::Svg {
+                path: PathBuf::from("logo.svg"),
+                symbol: Some("logo-dark".to_string()),
+                optimize: None,
+                monochrome: None,
+            },
+            ..moved
+        };
+        assert_eq!(locked.changes(&other_symbol), vec!["origin"]);
+    }
+
+    #[rstest]
+    #[case(
+        "/project/assets/logo.svg",
+        "/project/src/icons",
+        "../../assets/logo.svg"
+    )]
+    #[case("/project/src/icons/logo.svg", "/project/src/icons", "logo.svg")]
+    #[case("/project/src/./icons/../logo.svg", "/project", "src/logo.svg")]
+    fn test_relative_path(#[case] path: &str, #[case] base: &str, #[case] expected: &str) {
+        assert_eq!(
+            relative_path(Path::new(path), Path::new(base)),
+            PathBuf::from(expected)
+        );
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(LOCKFILE_NAME);
+
+        assert_eq!(Lockfile::load(&path)?, None);
+
+        let mut lockfile = Lockfile::default();
+        lockfile.icons.insert(
+            "mdi:home".to_string(),
+            LockedIcon {
+                origin: IconOrigin::Iconify {
+                    last_modified: Some(1700000000),
+                },
+                collection_version: Some("7.4.47".to_string()),
+                width: "24".to_string(),
+                height: "24".to_string(),
+                view_box: "0 0 24 24".to_string(),
+                hash: hash_body("<path/>"),
+                options: Some(IconOptions {
+                    width: Some(32.0),
+                    height: None,
+                    monochrome: None,
+                }),
+            },
+        );
+        lockfile.icons.insert(
+            "other:logo".to_string(),
+            LockedIcon {
+                origin: IconOrigin::IconSet {
+                    path: temp_dir.path().join("../other.json"),
+                    last_modified: None,
+                    monochrome: None,
+                },
+                collection_version: None,
+                width: "24".to_string(),
+                height: "24".to_string(),
+                view_box: "0 0 24 24".to_string(),
+                hash: hash_body("<path/>"),
+                options: None,
+            },
+        );
+        lockfile.icons.insert(
+            "my-icons:logo".to_string(),
+            LockedIcon {
+                origin: IconOrigin::Svg {
+                    path: temp_dir.path().join("assets/my-icons/logo.svg"),
+                    symbol: None,
+                    optimize: None,
+                    monochrome: None,
+                },
+                collection_version: None,
+                width: "48".to_string(),
+                height: "48".to_string(),
+                view_box: "0 0 48 48".to_string(),
+                hash: hash_body("<circle/>"),
+                options: None,
+            },
+        );
+
+        lockfile.save(&path)?;
+
+        let content = fs::read_to_string(&path)?;
+        assert!(content.starts_with(LOCKFILE_HEADER));
+        assert!(content.contains("[icons.\"mdi:home\"]"));
+        assert!(content.contains("origin = \"iconify\""));
+        assert!(content.contains("path = \"assets/my-icons/logo.svg\""));
+        assert!(content.contains("path = \"../other.json\""));
+
+        // Local paths are resolved against the lockfile directory
+        let loaded = Lockfile::load(&path)?.expect("lockfile should exist");
+        let mut other_origin = loaded.icons["other:logo"].origin.clone();
+        assert_eq!(
+            other_origin.path_mut().cloned(),
+            temp_dir.path().parent().map(|dir| dir.join("other.json"))
+        );
+        assert_eq!(
+            loaded.icons["my-icons:logo"],
+            lockfile.icons["my-icons:logo"]
+        );
+        assert_eq!(loaded.icons["mdi:home"], lockfile.icons["mdi:home"]);
+
+        Ok(())
+    }
+}
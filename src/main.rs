@@ -1,16 +1,34 @@
 mod api;
+mod archive;
+mod cache;
 mod generator;
+mod iconify_json;
+mod ids;
+mod lockfile;
+mod manifest;
+mod monochrome;
 mod naming;
+mod optimize;
+mod source;
 mod svg;
 
 use anyhow::{Context, Result, anyhow};
-use clap::{Parser, Subcommand};
-use std::collections::HashSet;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use api::IconifyClient;
-use generator::Generator;
+use archive::Archive;
+use cache::HttpCache;
+use generator::{Generator, write_if_changed};
+use iconify_json::IconSets;
+use manifest::Manifest;
+use monochrome::{Monochrome, Outcome};
 use naming::IconIdentifier;
+use optimize::Preset;
+use source::{Archives, IconSources, Resolved, SvgFiles};
 
 #[derive(Parser)]
 #[command(name = "dioxus-iconify")]
@@ -23,6 +41,72 @@ struct Cli {
     /// Output directory for generated icons (default: src/icons)
     #[arg(short, long, global = true, default_value = "src/icons")]
     output: PathBuf,
+
+    /// Path to the icons manifest (also read for `api_urls`)
+    #[arg(short, long, global = true, default_value = manifest::MANIFEST_FILE)]
+    manifest: PathBuf,
+
+    #[command(flatten)]
+    sources: SourceArgs,
+}
+
+/// Where icons are fetched from
+#[derive(Args)]
+struct SourceArgs {
+    /// Iconify API base URLs, tried in order with failover (default: https://api.iconify.design)
+    #[arg(
+        long = "api-url",
+        global = true,
+        env = "DIOXUS_ICONIFY_API_URL",
+        value_delimiter = ','
+    )]
+    api_urls: Vec<String>,
+
+    /// Directories of local IconifyJSON collection files (e.g., node_modules/@iconify/json),
+    /// used instead of the API for the collections they have
+    #[arg(long = "source", global = true)]
+    icon_set_dirs: Vec<PathBuf>,
+
+    /// Cache directory of the API responses (default: ~/.cache/dioxus-iconify)
+    #[arg(long, global = true, env = "DIOXUS_ICONIFY_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Only use cached API responses (no network)
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Timeout of each API request, in seconds
+    #[arg(long, global = true, default_value_t = api::DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+
+    /// Number of retries of failed API requests (connection errors, timeouts, 5xx, 429)
+    #[arg(long, global = true, default_value_t = api::RetryPolicy::default().max_retries)]
+    retries: u32,
+}
+
+/// How local files are imported (`add`, `update` and `sync`)
+#[derive(Args)]
+struct ImportArgs {
+    /// Split SVG files into one icon per `<symbol>` (sprite sheets with only symbols and
+    /// definitions are detected automatically)
+    #[arg(long)]
+    sprite: bool,
+
+    /// Only import the SVG files under this directory of archives (e.g., icons/)
+    #[arg(long = "path")]
+    archive_dir: Option<String>,
+
+    /// Collection of the icons imported from archives (default: the archive name without version)
+    #[arg(long)]
+    collection: Option<String>,
+
+    /// Optimize the body of local SVGs (recorded in icons.lock, and re-applied by `update`)
+    #[arg(long, value_enum)]
+    optimize: Option<Preset>,
+
+    /// Rewrite the colours of local icons to `currentColor` (recorded in icons.lock, and re-applied by `update`)
+    #[arg(long, value_enum)]
+    monochrome: Option<Monochrome>,
 }
 
 #[derive(Subcommand)]
@@ -37,6 +121,9 @@ enum Commands {
         /// Skip icons that already exist (don't overwrite)
         #[arg(long)]
         skip_existing: bool,
+
+        #[command(flatten)]
+        import: ImportArgs,
     },
 
     /// Initialize the icons directory (creates mod.rs)
@@ -47,21 +134,107 @@ enum Commands {
     #[command(visible_alias = "l")]
     List,
 
-    /// Update all icons by re-fetching from API
+    /// Update icons (re-fetch from API, re-import local SVG files)
     #[command(visible_alias = "u")]
-    Update,
-    // Future commands (not yet implemented)
-    // /// Remove icons from your project
-    // #[command(visible_alias = "r")]
-    // Remove {
-    //     icons: Vec<String>,
-    // },
+    Update {
+        /// Collections or icons to update, all when empty (e.g., mdi, mdi:home, lucide:*)
+        targets: Vec<String>,
+
+        #[command(flatten)]
+        import: ImportArgs,
+    },
+
+    /// Check that generated icons are up to date (without writing anything)
+    #[command(visible_alias = "c")]
+    Check,
+
+    /// Make the output directory match the icons manifest (icons.toml, see --manifest)
+    Sync {
+        #[command(flatten)]
+        import: ImportArgs,
+    },
+
+    /// Export the vendored icons for other frontends (one file per collection)
+    Export {
+        /// Format of the exported files
+        #[arg(long, value_enum, default_value_t = ExportFormat::IconifyJson)]
+        format: ExportFormat,
+
+        /// Directory where the exported files are written
+        #[arg(long, default_value = "iconify-json")]
+        out_dir: PathBuf,
+    },
+
+    /// Search icons on Iconify
+    #[command(visible_alias = "s")]
+    Search {
+        /// Search query (e.g., home, arrow left)
+        query: String,
+
+        /// Only search in this collection (e.g., mdi)
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long)]
+        limit: Option<u32>,
+
+        /// Only search in collections of this category (e.g., General)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Only colored (true) or monotone (false) icons
+        #[arg(long)]
+        palette: Option<bool>,
+
+        /// Print only identifiers, one per line (e.g., to pipe into `add`)
+        #[arg(long)]
+        plain: bool,
+    },
+
+    /// Remove one or more icons from your project
+    #[command(visible_alias = "r")]
+    Remove {
+        /// Icon identifiers, or collection:* to remove a whole collection (e.g., mdi:home, mdi:*)
+        #[arg(required = true)]
+        icons: Vec<String>,
+    },
+
+    /// Manage the cache of API responses
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// IconifyJSON collections (`<prefix>.json`)
+    IconifyJson,
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Show the cache directory, number of entries and size
+    Info,
+    /// Remove every cached response
+    Clear,
 }
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
     if let Err(err) = run().await {
         eprintln!("Error: {:#}", err);
+        if err.chain().any(|cause| cause.is::<api::NetworkError>()) {
+            eprintln!(
+                "\n💡 The Iconify API is unreachable: check your network, use --timeout/--retries,"
+            );
+            eprintln!(
+                "   or work offline with --offline (cached responses) or --source (IconifyJSON files)"
+            );
+        } else if err.chain().any(|cause| cause.is::<api::IconNotFound>()) {
+            eprintln!("\n💡 Find icon names with: dioxus-iconify search <query>");
+        }
         std::process::exit(1);
     }
 }
@@ -74,8 +247,18 @@ async fn run() -> Result<()> {
         Commands::Add {
             icons,
             skip_existing,
+            import,
         } => {
-            add_icons(&generator, &icons, skip_existing).await?;
+            let sources = create_sources(&cli.sources, &import, &cli.manifest)?;
+            add_icons(
+                &generator,
+                &sources,
+                &icons,
+                skip_existing,
+                import.optimize,
+                import.monochrome,
+            )
+            .await?;
         }
         Commands::Init => {
             init_icons_dir(&generator)?;
@@ -83,142 +266,133 @@ async fn run() -> Result<()> {
         Commands::List => {
             list_icons(&generator)?;
         }
-        Commands::Update => {
-            update_icons(&generator).await?;
+        Commands::Update { targets, import } => {
+            let sources = create_sources(&cli.sources, &import, &cli.manifest)?;
+            update_icons(
+                &generator,
+                &sources,
+                &targets,
+                import.optimize,
+                import.monochrome,
+            )
+            .await?;
         }
-    }
-
-    Ok(())
-}
-
-async fn add_icons(generator: &Generator, inputs: &[String], skip_existing: bool) -> Result<()> {
-    // Classify inputs into three categories
-    let mut api_identifiers = Vec::new();
-    let mut svg_files = Vec::new();
-    let mut svg_directories = Vec::new();
-
-    for input in inputs {
-        let path = Path::new(input);
-
-        if path.exists() {
-            if path.is_dir() {
-                svg_directories.push(path.to_path_buf());
-            } else if path.extension().and_then(|s| s.to_str()) == Some("svg") {
-                svg_files.push(path.to_path_buf());
-            } else {
-                return Err(anyhow!(
-                    "Path exists but is not SVG file or directory: {}",
-                    input
-                ));
+        Commands::Check => {
+            check_icons(&generator)?;
+        }
+        Commands::Sync { import } => {
+            let sources = create_sources(&cli.sources, &import, &cli.manifest)?;
+            sync_icons(
+                &generator,
+                &sources,
+                &cli.manifest,
+                import.optimize,
+                import.monochrome,
+            )
+            .await?;
+        }
+        Commands::Export { format, out_dir } => {
+            export_icons(&generator, format, &out_dir)?;
+        }
+        Commands::Search {
+            query,
+            prefix,
+            limit,
+            category,
+            palette,
+            plain,
+        } => {
+            let options = api::SearchOptions {
+                prefix,
+                limit,
+                category,
+                palette,
+            };
+            let client = create_client(&cli.sources, &cli.manifest)?;
+            search_icons(&client, &query, &options, plain).await?;
+        }
+        Commands::Remove { icons } => {
+            remove_icons(&generator, &icons)?;
+        }
+        Commands::Cache { command } => {
+            let cache = http_cache(&cli.sources)?;
+            match command {
+                CacheCommands::Info => cache_info(&cache)?,
+                CacheCommands::Clear => cache_clear(&cache)?,
             }
-        } else {
-            // Not a filesystem path, treat as API identifier
-            api_identifiers.push(input.clone());
         }
     }
 
-    let client = IconifyClient::new()?;
-    let mut icons_to_add = Vec::new();
-    let mut collections = HashSet::new();
-    let mut api_collections = HashSet::new(); // Track which collections came from API
-
-    // Process API icons (existing logic)
-    if !api_identifiers.is_empty() {
-        println!(
-            "📦 Fetching {} icon(s) from Iconify API...",
-            api_identifiers.len()
-        );
-
-        for icon_id in &api_identifiers {
-            // Parse icon identifier
-            let identifier = IconIdentifier::parse(icon_id)
-                .context(format!("Invalid icon identifier: {}", icon_id))?;
-
-            // Track collections
-            collections.insert(identifier.collection.clone());
-            api_collections.insert(identifier.collection.clone());
-
-            // Fetch icon from API
-            print!("  Fetching {}... ", icon_id);
-            let icon = client
-                .fetch_icon(&identifier.collection, &identifier.icon_name)
-                .await
-                .context(format!("Failed to fetch icon: {}", icon_id))?;
-
-            println!("✓");
+    Ok(())
+}
 
-            icons_to_add.push((identifier, icon));
-        }
+/// Create the API client, with the base URLs from (first non-empty):
+/// `--api-url` / `DIOXUS_ICONIFY_API_URL`, the manifest `api_urls`, the public Iconify API
+fn create_client(sources: &SourceArgs, manifest_path: &Path) -> Result<IconifyClient> {
+    let mut api_urls = sources.api_urls.clone();
+    if api_urls.is_empty() && manifest_path.exists() {
+        api_urls = Manifest::load(manifest_path)?.api_urls;
     }
 
-    // Process local SVG files (NEW)
-    if !svg_files.is_empty() {
-        println!("\n📁 Processing {} local SVG file(s)...", svg_files.len());
-        for svg_path in &svg_files {
-            match process_single_svg(svg_path) {
-                Ok((identifier, icon)) => {
-                    println!("  {} ✓", identifier.full_name);
-                    collections.insert(identifier.collection.clone());
-                    icons_to_add.push((identifier, icon));
-                }
-                Err(e) => {
-                    eprintln!("  ⚠ Skipping {}: {}", svg_path.display(), e);
-                }
-            }
-        }
-    }
+    let client = if api_urls.is_empty() {
+        IconifyClient::new()?
+    } else {
+        IconifyClient::with_base_urls(&api_urls)?
+    };
 
-    // Process SVG directories (NEW)
-    if !svg_directories.is_empty() {
-        println!(
-            "\n📂 Scanning {} director(ies) for SVGs...",
-            svg_directories.len()
-        );
-        for dir_path in &svg_directories {
-            let collection = match svg::extract_collection_name(dir_path) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("  ⚠ Skipping {}: {}", dir_path.display(), e);
-                    continue;
-                }
-            };
+    Ok(client
+        .with_timeout(Duration::from_secs(sources.timeout))
+        .with_retry_policy(api::RetryPolicy {
+            max_retries: sources.retries,
+            ..Default::default()
+        })
+        .with_cache(http_cache(sources)?, sources.offline))
+}
 
-            let svg_files = match svg::scan_svg_directory(dir_path) {
-                Ok(files) => files,
-                Err(e) => {
-                    eprintln!("  ⚠ Error scanning {}: {}", dir_path.display(), e);
-                    continue;
-                }
-            };
+/// Create the icon sources, tried in order for inputs without scheme: existing archives
+/// (`archive:`), local IconifyJSON collections and files (`json:`), existing paths
+/// (`file:`), the Iconify API (`iconify:`)
+fn create_sources(
+    sources: &SourceArgs,
+    import: &ImportArgs,
+    manifest_path: &Path,
+) -> Result<IconSources> {
+    Ok(IconSources::new(vec![
+        Box::new(Archives {
+            dir: import.archive_dir.clone(),
+            collection: import.collection.clone(),
+        }),
+        Box::new(IconSets::new(&sources.icon_set_dirs)?),
+        Box::new(SvgFiles {
+            sprite: import.sprite,
+        }),
+        Box::new(create_client(sources, manifest_path)?),
+    ]))
+}
 
-            if !svg_files.is_empty() {
-                println!(
-                    "  Found {} SVG(s) in {}",
-                    svg_files.len(),
-                    dir_path.display()
-                );
-            }
+/// The cache of API responses, in `--cache-dir` or the default user cache directory
+fn http_cache(sources: &SourceArgs) -> Result<HttpCache> {
+    sources
+        .cache_dir
+        .clone()
+        .or_else(HttpCache::default_dir)
+        .map(HttpCache::new)
+        .ok_or_else(|| anyhow!("No cache directory found, use --cache-dir"))
+}
 
-            for (svg_path, icon_name) in svg_files {
-                let full_name = format!("{}:{}", collection, icon_name);
-
-                match IconIdentifier::parse(&full_name) {
-                    Ok(identifier) => match svg::parse_svg_file(&svg_path) {
-                        Ok(icon) => {
-                            collections.insert(collection.clone());
-                            icons_to_add.push((identifier, icon));
-                        }
-                        Err(e) => {
-                            eprintln!("  ⚠ Skipping {}: {}", svg_path.display(), e);
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("  ⚠ Invalid icon name {}: {}", full_name, e);
-                    }
-                }
-            }
-        }
-    }
+async fn add_icons(
+    generator: &Generator,
+    sources: &IconSources,
+    inputs: &[String],
+    skip_existing: bool,
+    optimize: Option<Preset>,
+    monochrome: Option<Monochrome>,
+) -> Result<()> {
+    let Resolved {
+        icons: mut icons_to_add,
+        mut collections,
+        ..
+    } = sources.resolve(inputs).await?.check()?;
 
     if icons_to_add.is_empty() {
         println!("\n⚠ No icons to add");
@@ -250,24 +424,17 @@ async fn add_icons(generator: &Generator, inputs: &[String], skip_existing: bool
         }
     }
 
-    // Fetch collection info only for API collections (not local SVGs)
-    let mut collection_info = std::collections::HashMap::new();
-    if !api_collections.is_empty() {
-        println!("\n📚 Fetching collection metadata...");
-        for collection in &api_collections {
-            print!("  Fetching info for {}... ", collection);
-            match client.fetch_collection_info(collection).await {
-                Ok(info) => {
-                    println!("✓");
-                    collection_info.insert(collection.clone(), info);
-                }
-                Err(e) => {
-                    println!("⚠ (skipped: {})", e);
-                    // Continue without collection info - it's optional
-                }
-            }
-        }
-    }
+    optimize_icons(&mut icons_to_add, |_| optimize)?;
+    normalize_colors(&mut icons_to_add, |_| monochrome)?;
+    prefix_icon_ids(&mut icons_to_add);
+
+    // Fetch collection info only for collections with metadata (not local SVGs)
+    collections.retain(|collection, _| {
+        icons_to_add
+            .iter()
+            .any(|(identifier, _)| identifier.collection == *collection)
+    });
+    let collection_info = sources.collections_info(&collections).await;
 
     // Generate code
     println!("\n📝 Generating Rust code...");
@@ -295,25 +462,175 @@ async fn add_icons(generator: &Generator, inputs: &[String], skip_existing: bool
     Ok(())
 }
 
-/// Helper function to process a single SVG file
-fn process_single_svg(svg_path: &Path) -> Result<(IconIdentifier, api::IconifyIcon)> {
-    let collection = svg::extract_collection_name(
-        svg_path
-            .parent()
-            .ok_or_else(|| anyhow!("No parent directory for: {}", svg_path.display()))?,
-    )?;
-
-    let icon_name = svg_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .ok_or_else(|| anyhow!("Invalid filename: {}", svg_path.display()))?
-        .to_string();
-
-    let full_name = format!("{}:{}", collection, icon_name);
-    let identifier = IconIdentifier::parse(&full_name)?;
-    let icon = svg::parse_svg_file(svg_path)?;
-
-    Ok((identifier, icon))
+fn check_icons(generator: &Generator) -> Result<()> {
+    println!("🔍 Checking generated icons...");
+
+    let problems = generator.check()?;
+
+    if problems.is_empty() {
+        println!("\n✨ All generated icons are up to date.");
+        return Ok(());
+    }
+
+    println!();
+    for (file_name, file_problems) in &problems {
+        println!("✗ {}", file_name);
+        for problem in file_problems {
+            println!("  - {}", problem);
+        }
+    }
+
+    Err(anyhow!(
+        "{} file(s) out of date, run `dioxus-iconify update` to regenerate them",
+        problems.len()
+    ))
+}
+
+fn export_icons(generator: &Generator, format: ExportFormat, out_dir: &Path) -> Result<()> {
+    let collections = match format {
+        ExportFormat::IconifyJson => generator.export_iconify_json()?,
+    };
+
+    if collections.is_empty() {
+        println!("No icons to export.");
+        return Ok(());
+    }
+
+    println!(
+        "📤 Exporting {} collection(s) to {}...",
+        collections.len(),
+        out_dir.display()
+    );
+    fs::create_dir_all(out_dir).context(format!("Failed to create {}", out_dir.display()))?;
+
+    for (prefix, collection) in &collections {
+        let path = out_dir.join(format!("{}.json", prefix));
+        let content = serde_json::to_string_pretty(collection)? + "\n";
+        write_if_changed(&path, &content)?;
+        println!(
+            "  ✓ {} ({} icon(s))",
+            path.display(),
+            collection.icons.len()
+        );
+    }
+
+    println!("\n✨ Done! Exported {} collection(s)", collections.len());
+
+    Ok(())
+}
+
+/// Make the output directory match the manifest: add missing icons, remove unlisted ones
+async fn sync_icons(
+    generator: &Generator,
+    sources: &IconSources,
+    manifest_path: &Path,
+    optimize: Option<Preset>,
+    monochrome: Option<Monochrome>,
+) -> Result<()> {
+    println!("🔄 Syncing icons with {}...", manifest_path.display());
+
+    let manifest = Manifest::load(manifest_path)?;
+    let existing: HashSet<String> = generator
+        .get_generated_icon_identifiers()?
+        .into_iter()
+        .collect();
+    let lockfile = generator.lockfile()?;
+
+    // Local paths are relative to the manifest
+    let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let mut listed = HashSet::new();
+    let mut inputs = Vec::new();
+
+    for input in &manifest.icons {
+        let (source, rest) = sources.split_scheme(input);
+        let path = base_dir.join(rest);
+        let is_path = match source {
+            Some(source) => matches!(source.scheme(), "file" | "archive"),
+            None => path.exists(),
+        } || iconify_json::split_file_input(&path.to_string_lossy()).is_some();
+        if is_path {
+            // Local files are cheap to re-read, always re-import them
+            inputs.push(path.to_string_lossy().to_string());
+        } else {
+            let identifier = IconIdentifier::parse(rest)
+                .context(format!("Invalid icon identifier in manifest: {}", input))?;
+            // Only fetch API icons that are missing, or whose options changed since
+            // they were generated (options added, changed or removed)
+            let options = manifest.icon_options(&identifier);
+            if !existing.contains(&identifier.full_name)
+                || lockfile
+                    .icons
+                    .get(&identifier.full_name)
+                    .is_none_or(|locked| locked.options != options)
+            {
+                inputs.push(input.clone());
+            }
+            listed.insert(identifier.full_name);
+        }
+    }
+
+    let Resolved {
+        mut icons,
+        collections,
+        ..
+    } = sources.resolve(&inputs).await?.check()?;
+
+    for (identifier, icon) in &mut icons {
+        manifest.apply_options(identifier, icon);
+        listed.insert(identifier.full_name.clone());
+    }
+    optimize_icons(&mut icons, |_| optimize)?;
+    // The manifest's colour mode of an icon (or its collection) takes precedence over the flag
+    normalize_colors(&mut icons, |identifier| {
+        manifest
+            .icon_options(identifier)
+            .and_then(|options| options.monochrome)
+            .or(monochrome)
+    })?;
+    prefix_icon_ids(&mut icons);
+
+    // Remove icons that are no longer listed
+    let mut to_remove = existing
+        .iter()
+        .filter(|icon_id| !listed.contains(*icon_id))
+        .map(|icon_id| IconIdentifier::parse(icon_id))
+        .collect::<Result<Vec<_>>>()?;
+    to_remove.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+
+    let removed = if to_remove.is_empty() {
+        Vec::new()
+    } else {
+        println!("\n🗑 Removing {} unlisted icon(s)...", to_remove.len());
+        generator.remove_icons(&to_remove)?
+    };
+
+    if !icons.is_empty() {
+        let collection_info = sources.collections_info(&collections).await;
+
+        println!("\n📝 Generating Rust code...");
+        generator.add_icons(&icons, &collection_info)?;
+        generator.record_options(icons.iter().map(|(identifier, _)| {
+            (
+                identifier.full_name.clone(),
+                manifest.icon_options(identifier),
+            )
+        }))?;
+    }
+
+    generator.regenerate_mod_rs()?;
+
+    let added = icons
+        .iter()
+        .filter(|(identifier, _)| !existing.contains(&identifier.full_name))
+        .count();
+    println!(
+        "\n✨ Synced {} icon(s): {} added, {} removed.",
+        listed.len(),
+        added,
+        removed.len()
+    );
+
+    Ok(())
 }
 
 fn init_icons_dir(generator: &Generator) -> Result<()> {
@@ -358,92 +675,457 @@ fn list_icons(generator: &Generator) -> Result<()> {
     Ok(())
 }
 
-async fn update_icons(generator: &Generator) -> Result<()> {
-    println!("🔄 Updating all icons...");
+fn cache_info(cache: &HttpCache) -> Result<()> {
+    let info = cache.info()?;
 
-    // Get all existing icon identifiers
-    let icon_ids = generator.get_all_icon_identifiers()?;
+    println!("📦 Cache: {}", cache.dir().display());
+    println!(
+        "  {} response(s), {} KiB",
+        info.entries,
+        info.size.div_ceil(1024)
+    );
 
-    if icon_ids.is_empty() {
-        println!("No icons to update.");
-        println!("\n💡 Add icons first with: dioxus-iconify add <icon>");
-        println!("   Example: dioxus-iconify add mdi:home");
+    Ok(())
+}
+
+fn cache_clear(cache: &HttpCache) -> Result<()> {
+    let removed = cache.clear()?;
+
+    println!(
+        "🗑  Removed {} cached response(s) from {}",
+        removed,
+        cache.dir().display()
+    );
+
+    Ok(())
+}
+
+async fn search_icons(
+    client: &IconifyClient,
+    query: &str,
+    options: &api::SearchOptions,
+    plain: bool,
+) -> Result<()> {
+    let result = client.search(query, options).await?;
+
+    if plain {
+        for icon_id in &result.icons {
+            println!("{}", icon_id);
+        }
         return Ok(());
     }
 
-    println!("📦 Found {} icon(s) to update", icon_ids.len());
-    println!("\n🌐 Fetching latest versions from Iconify API...");
+    if result.icons.is_empty() {
+        println!("No icons found for '{}'.", query);
+        return Ok(());
+    }
 
-    let client = IconifyClient::new()?;
-    let mut icons_to_update = Vec::new();
-    let mut failed_icons = Vec::new();
-    let mut collections = std::collections::HashSet::new();
+    // Group results by collection
+    let mut icons_by_collection: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for icon_id in &result.icons {
+        let identifier = IconIdentifier::parse(icon_id)?;
+        icons_by_collection
+            .entry(identifier.collection)
+            .or_default()
+            .push(identifier.full_name);
+    }
+
+    println!(
+        "🔍 Found {} icon(s) across {} collection(s):\n",
+        result.icons.len(),
+        icons_by_collection.len()
+    );
+
+    for (collection, icons) in &icons_by_collection {
+        match result
+            .collections
+            .get(collection)
+            .and_then(|i| i.name.as_ref())
+        {
+            Some(name) => println!("{} ({})", collection, name),
+            None => println!("{}", collection),
+        }
+        for icon in icons {
+            println!("  {}", icon);
+        }
+        println!();
+    }
+
+    if result.limit > 0 && result.total >= result.limit {
+        println!(
+            "⚠ Results limited to {}, use --limit to see more\n",
+            result.limit
+        );
+    }
+
+    println!("💡 Add icons with: dioxus-iconify add {}", result.icons[0]);
+    println!("   Or all of them: dioxus-iconify add $(dioxus-iconify search --plain ...)");
+
+    Ok(())
+}
+
+fn remove_icons(generator: &Generator, inputs: &[String]) -> Result<()> {
+    let identifiers = inputs
+        .iter()
+        .map(|input| {
+            IconIdentifier::parse(input).context(format!("Invalid icon identifier: {}", input))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    println!("🗑 Removing {} icon(s)...", identifiers.len());
+    let removed = generator.remove_icons(&identifiers)?;
+
+    if removed.is_empty() {
+        println!("\n⚠ No matching icons found");
+        return Ok(());
+    }
+
+    println!("\n✨ Done! Removed {} icon(s):", removed.len());
+    for icon_id in &removed {
+        println!("  - {}", icon_id);
+    }
+
+    Ok(())
+}
+
+/// Optimize the body of local icons, with the preset of each icon (if any)
+fn optimize_icons(
+    icons: &mut [(IconIdentifier, api::IconifyIcon)],
+    preset_of: impl Fn(&IconIdentifier) -> Option<Preset>,
+) -> Result<()> {
+    let mut reports = Vec::new();
+    for (identifier, icon) in icons {
+        let Some(preset) = preset_of(identifier) else {
+            continue;
+        };
+        if let Some(report) = optimize::optimize_icon(icon, preset)
+            .context(format!("Failed to optimize {}", identifier.full_name))?
+        {
+            reports.push((identifier.full_name.clone(), preset, report));
+        }
+    }
+
+    if !reports.is_empty() {
+        println!("\n🧹 Optimized {} SVG(s):", reports.len());
+        for (icon_id, preset, report) in reports {
+            println!("  {} ({}): {}", icon_id, preset, report);
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrite the colours of local icons to `currentColor`, warning about the multi-colour
+/// icons kept as-is
+fn normalize_colors(
+    icons: &mut [(IconIdentifier, api::IconifyIcon)],
+    mode_of: impl Fn(&IconIdentifier) -> Option<Monochrome>,
+) -> Result<()> {
+    let mut converted = Vec::new();
+    for (identifier, icon) in icons {
+        let Some(mode) = mode_of(identifier) else {
+            continue;
+        };
+        match monochrome::normalize_icon(icon, mode).context(format!(
+            "Failed to normalize the colours of {}",
+            identifier.full_name
+        ))? {
+            Some(Outcome::Converted(colors)) => {
+                converted.push((identifier.full_name.clone(), colors))
+            }
+            Some(Outcome::MultiColor(colors)) => eprintln!(
+                "  ⚠ {} has {} colours ({}), kept as-is (use the `force` mode to convert it anyway)",
+                identifier.full_name,
+                colors.len(),
+                colors.join(", ")
+            ),
+            Some(Outcome::Unchanged) | None => {}
+        }
+    }
+
+    if !converted.is_empty() {
+        println!(
+            "\n🎨 Converted {} icon(s) to currentColor:",
+            converted.len()
+        );
+        for (icon_id, colors) in converted {
+            println!("  {} ({})", icon_id, colors.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prefix the IDs of icon bodies with their icon name, so that the gradients, clip paths or
+/// masks of icons on the same page don't clash (a body that can't be parsed is kept as-is)
+fn prefix_icon_ids(icons: &mut [(IconIdentifier, api::IconifyIcon)]) {
+    for (identifier, icon) in icons {
+        match ids::prefix_ids(&icon.body, &ids::id_prefix(identifier)) {
+            Ok(body) => icon.body = body,
+            Err(e) => eprintln!("  ⚠ {}: IDs not prefixed ({:#})", identifier.full_name, e),
+        }
+    }
+}
 
-    for icon_id in &icon_ids {
+/// Check whether an icon is selected by `update` targets
+/// (`mdi` or `mdi:*` for a collection, `mdi:home` for a single icon, everything when empty)
+fn matches_targets(identifier: &IconIdentifier, targets: &[String]) -> bool {
+    targets.is_empty()
+        || targets.iter().any(|target| match target.split_once(':') {
+            Some((collection, "*")) => collection == identifier.collection,
+            Some(_) => *target == identifier.full_name,
+            None => *target == identifier.collection,
+        })
+}
+
+async fn update_icons(
+    generator: &Generator,
+    sources: &IconSources,
+    targets: &[String],
+    optimize: Option<Preset>,
+    monochrome: Option<Monochrome>,
+) -> Result<()> {
+    if targets.is_empty() {
+        println!("🔄 Updating all icons...");
+    } else {
+        println!("🔄 Updating {}...", targets.join(", "));
+    }
+
+    // Get all generated icons with their origin (user-written modules are not updated)
+    let lockfile = generator.lockfile()?;
+
+    let mut selected = Vec::new();
+    for (icon_id, locked) in &lockfile.icons {
         // Parse icon identifier
-        let identifier = match IconIdentifier::parse(icon_id) {
-            Ok(id) => id,
+        match IconIdentifier::parse(icon_id) {
+            Ok(identifier) if matches_targets(&identifier, targets) => {
+                selected.push((identifier, locked));
+            }
+            Ok(_) => {}
             Err(e) => {
                 eprintln!("  ⚠ Skipping invalid icon identifier {}: {}", icon_id, e);
-                failed_icons.push(icon_id.clone());
-                continue;
             }
-        };
+        }
+    }
 
-        // Track collections
-        collections.insert(identifier.collection.clone());
+    if selected.is_empty() {
+        println!("No icons to update.");
+        println!("\n💡 Add icons first with: dioxus-iconify add <icon>");
+        println!("   Example: dioxus-iconify add mdi:home");
+        return Ok(());
+    }
 
-        // Fetch icon from API
-        print!("  Fetching {}... ", icon_id);
-        match client
-            .fetch_icon(&identifier.collection, &identifier.icon_name)
-            .await
-        {
-            Ok(icon) => {
-                println!("✓");
-                icons_to_update.push((identifier, icon));
+    println!("📦 Found {} icon(s) to update", selected.len());
+
+    let mut fetched = Vec::new();
+    let mut removed_icons = Vec::new();
+    let mut missing_sources = Vec::new();
+    let mut failed_icons = Vec::new();
+    let mut iconify_inputs = Vec::new();
+    let mut svg_files = Vec::new();
+    let mut archive_entries: BTreeMap<PathBuf, Vec<(IconIdentifier, String)>> = BTreeMap::new();
+    let mut collection_sources = BTreeMap::new();
+
+    for (identifier, locked) in &selected {
+        match &locked.origin {
+            api::IconOrigin::Iconify { .. } => iconify_inputs.push(identifier.full_name.clone()),
+            api::IconOrigin::IconSet { path, .. } if !path.is_file() => {
+                eprintln!("  ⚠ {}: {} not found", identifier.full_name, path.display());
+                missing_sources.push((identifier.full_name.clone(), path.clone()));
             }
-            Err(e) => {
-                println!("✗");
-                eprintln!("    Error: {}", e);
-                failed_icons.push(icon_id.clone());
+            // Re-read from the same custom IconifyJSON file
+            api::IconOrigin::IconSet { path, .. } => {
+                iconify_inputs.push(format!("json:{}:{}", path.display(), identifier.icon_name))
             }
+            api::IconOrigin::Svg { path, symbol, .. } => {
+                svg_files.push((identifier.clone(), path.clone(), symbol.clone()))
+            }
+            api::IconOrigin::Archive { path, entry, .. } => archive_entries
+                .entry(path.clone())
+                .or_default()
+                .push((identifier.clone(), entry.clone())),
         }
     }
 
-    if icons_to_update.is_empty() {
-        eprintln!("\n❌ Failed to fetch any icons");
-        return Ok(());
+    // Re-import local SVGs from their original files
+    if !svg_files.is_empty() {
+        println!("\n📁 Re-importing {} local SVG file(s)...", svg_files.len());
+        for (identifier, path, symbol) in svg_files {
+            print!("  Parsing {}... ", path.display());
+            if !path.exists() {
+                println!("✗");
+                eprintln!("    Error: file not found");
+                missing_sources.push((identifier.full_name, path));
+                continue;
+            }
+            let parsed = match &symbol {
+                Some(symbol) => svg::parse_sprite_symbol(&path, symbol),
+                None => svg::parse_svg_file(&path).map(Some),
+            };
+            match parsed {
+                Ok(None) => {
+                    println!("✗");
+                    eprintln!(
+                        "    Error: symbol '{}' not found",
+                        symbol.unwrap_or_default()
+                    );
+                    removed_icons.push(identifier.full_name);
+                }
+                Ok(Some(icon)) => {
+                    println!("✓");
+                    fetched.push((identifier, icon));
+                }
+                Err(e) => {
+                    println!("✗");
+                    eprintln!("    Error: {}", e);
+                    failed_icons.push(identifier.full_name);
+                }
+            }
+        }
     }
 
-    // Fetch collection info for all unique collections
-    println!("\n📚 Fetching collection metadata...");
-    let mut collection_info = std::collections::HashMap::new();
-    for collection in collections {
-        print!("  Fetching info for {}... ", collection);
-        match client.fetch_collection_info(&collection).await {
-            Ok(info) => {
-                println!("✓");
-                collection_info.insert(collection, info);
+    // Re-import icons from their archives, reading each archive once
+    if !archive_entries.is_empty() {
+        println!(
+            "\n📦 Re-importing {} icon(s) from archives...",
+            archive_entries.values().map(Vec::len).sum::<usize>()
+        );
+        for (path, entries) in archive_entries {
+            print!("  Reading {}... ", path.display());
+            if !path.exists() {
+                println!("✗");
+                eprintln!("    Error: file not found");
+                missing_sources.extend(
+                    entries
+                        .into_iter()
+                        .map(|(id, _)| (id.full_name, path.clone())),
+                );
+                continue;
             }
-            Err(e) => {
-                println!("⚠ (skipped: {})", e);
-                // Continue without collection info - it's optional
+            let archive = match Archive::read(&path) {
+                Ok(archive) => {
+                    println!("✓ ({})", archive.hash);
+                    archive
+                }
+                Err(e) => {
+                    println!("✗");
+                    eprintln!("    Error: {:#}", e);
+                    failed_icons.extend(entries.into_iter().map(|(id, _)| id.full_name));
+                    continue;
+                }
+            };
+
+            for (identifier, entry) in entries {
+                let Some(content) = archive.svg_files.get(&entry) else {
+                    removed_icons.push(identifier.full_name);
+                    continue;
+                };
+                let origin = api::IconOrigin::Archive {
+                    path: path.clone(),
+                    entry,
+                    archive_hash: archive.hash.clone(),
+                    optimize: None,
+                    monochrome: None,
+                };
+                match svg::parse_svg(content, origin) {
+                    Ok(icon) => fetched.push((identifier, icon)),
+                    Err(e) => {
+                        eprintln!("    Error: {}: {}", identifier.full_name, e);
+                        failed_icons.push(identifier.full_name);
+                    }
+                }
             }
         }
     }
 
-    // Regenerate code
-    println!("\n📝 Regenerating Rust code...");
-    generator.add_icons(&icons_to_update, &collection_info)?;
+    // Only Iconify icons are fetched (from local IconifyJSON files or the API)
+    if !iconify_inputs.is_empty() {
+        println!("\n🌐 Fetching latest versions of Iconify icons...");
+
+        let resolved = sources.resolve(&iconify_inputs).await?;
+
+        for failure in resolved.failed {
+            println!("  {} ✗", failure.label);
+            eprintln!("    Error: {:#}", failure.error);
+            failed_icons.extend(failure.inputs);
+        }
+        removed_icons.extend(resolved.not_found);
+        fetched.extend(resolved.icons);
+        collection_sources = resolved.collections;
+    }
+
+    // Re-apply the recorded optimization presets and colour modes (unless others are given)
+    optimize_icons(&mut fetched, |identifier| {
+        optimize.or_else(|| {
+            lockfile
+                .icons
+                .get(&identifier.full_name)
+                .and_then(|locked| locked.origin.optimize())
+        })
+    })?;
+    normalize_colors(&mut fetched, |identifier| {
+        monochrome.or_else(|| {
+            lockfile
+                .icons
+                .get(&identifier.full_name)
+                .and_then(|locked| locked.origin.monochrome())
+        })
+    })?;
+    prefix_icon_ids(&mut fetched);
+
+    // Compare with the recorded icons, only changed icons are rewritten
+    let mut unchanged_count = 0;
+    let mut changed_icons = Vec::new();
+    let mut icons_to_update = Vec::new();
+    let mut collections = BTreeMap::new();
+
+    for (identifier, icon) in fetched {
+        let changes = lockfile
+            .icons
+            .get(&identifier.full_name)
+            .map(|locked| locked.changes(&icon))
+            .unwrap_or_default();
+
+        if changes.is_empty() {
+            unchanged_count += 1;
+            continue;
+        }
+
+        if let Some(scheme) = collection_sources.get(&identifier.collection) {
+            collections.insert(identifier.collection.clone(), *scheme);
+        }
+        changed_icons.push((identifier.full_name.clone(), changes));
+        icons_to_update.push((identifier, icon));
+    }
+
+    if !icons_to_update.is_empty() {
+        // Fetch collection info for collections with changed icons
+        let collection_info = sources.collections_info(&collections).await;
+
+        // Regenerate code
+        println!("\n📝 Regenerating Rust code...");
+        generator.add_icons(&icons_to_update, &collection_info)?;
+    }
 
     // Force regenerate mod.rs to ensure Icon component is up to date
     generator.regenerate_mod_rs()?;
 
-    println!(
-        "\n✨ Updated {} icon(s) successfully!",
-        icons_to_update.len()
-    );
+    println!("\n📊 Update summary:");
+    println!("  ✓ {} unchanged", unchanged_count);
+    println!("  ✎ {} changed", changed_icons.len());
+    for (icon_id, changes) in &changed_icons {
+        println!("    - {} ({})", icon_id, changes.join(", "));
+    }
+    println!("  ✗ {} removed upstream", removed_icons.len());
+    for icon_id in &removed_icons {
+        println!("    - {}", icon_id);
+    }
+    if !missing_sources.is_empty() {
+        println!("  ✗ {} missing local source(s)", missing_sources.len());
+        for (icon_id, path) in &missing_sources {
+            println!("    - {} ({})", icon_id, path.display());
+        }
+    }
 
     if !failed_icons.is_empty() {
         println!("\n⚠ Failed to update {} icon(s):", failed_icons.len());
@@ -452,5 +1134,122 @@ async fn update_icons(generator: &Generator) -> Result<()> {
         }
     }
 
+    if !removed_icons.is_empty() || !missing_sources.is_empty() {
+        println!(
+            "\n💡 Removed icons and icons of missing sources are kept, drop them with: dioxus-iconify remove <icon>"
+        );
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use source::testing::{FakeSource, icon};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn fake_sources(icons: &[(&str, &str)]) -> IconSources {
+        IconSources::new(vec![
+            Box::new(SvgFiles::default()),
+            Box::new(FakeSource::new("iconify", icons)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_add_icons_from_fake_source() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let generator = Generator::new(temp_dir.path().join("icons"));
+        let sources = fake_sources(&[("mdi:home", "<path/>"), ("mdi:star", "<rect/>")]);
+
+        add_icons(
+            &generator,
+            &sources,
+            &["mdi:home".to_string(), "iconify:mdi:star".to_string()],
+            false,
+            None,
+            None,
+        )
+        .await?;
+
+        let content = fs::read_to_string(temp_dir.path().join("icons/mdi.rs"))?;
+        assert!(content.contains("pub const Home: IconData"));
+        assert!(content.contains("pub const Star: IconData"));
+        assert!(content.contains("mdi from iconify"), "Collection info");
+
+        let err = add_icons(
+            &generator,
+            &sources,
+            &["mdi:ghost".to_string()],
+            false,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.chain().any(|cause| cause.is::<api::IconNotFound>()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_icons_from_fake_source() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let generator = Generator::new(temp_dir.path().join("icons"));
+        generator.add_icons(
+            &[
+                (IconIdentifier::parse("mdi:home")?, icon("<path/>")),
+                (IconIdentifier::parse("mdi:gone")?, icon("<circle/>")),
+                (IconIdentifier::parse("lucide:star")?, icon("<rect/>")),
+            ],
+            &Default::default(),
+        )?;
+
+        let mut upstream = FakeSource::new(
+            "iconify",
+            &[
+                ("mdi:home", "<path d=\"M1 1\"/>"),
+                ("lucide:star", "<rect/>"),
+            ],
+        );
+        upstream.broken.push("lucide".to_string());
+        let sources = IconSources::new(vec![Box::new(upstream)]);
+
+        update_icons(&generator, &sources, &[], None, None).await?;
+
+        let content = fs::read_to_string(temp_dir.path().join("icons/mdi.rs"))?;
+        assert!(content.contains(r#"<path d="M1 1"/>"#), "Changed icon");
+        assert!(content.contains("pub const Gone"), "Removed icons are kept");
+        let lucide = fs::read_to_string(temp_dir.path().join("icons/lucide.rs"))?;
+        assert!(lucide.contains("<rect/>"), "Failed icons are kept");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_reapplies_changed_options() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let generator = Generator::new(temp_dir.path().join("icons"));
+        let manifest_path = temp_dir.path().join("icons.toml");
+        let sources = fake_sources(&[("mdi:home", "<path/>")]);
+
+        fs::write(
+            &manifest_path,
+            "icons = [\"mdi:home\"]\n\n[options.\"mdi:home\"]\nwidth = 32\n",
+        )?;
+        sync_icons(&generator, &sources, &manifest_path, None, None).await?;
+        let locked = generator.lockfile()?.icons["mdi:home"].clone();
+        assert_eq!(locked.width, "32");
+        assert_eq!(locked.options.and_then(|options| options.width), Some(32.0));
+
+        // Removing the options restores the icon's own size
+        fs::write(&manifest_path, "icons = [\"mdi:home\"]\n")?;
+        sync_icons(&generator, &sources, &manifest_path, None, None).await?;
+        let locked = generator.lockfile()?.icons["mdi:home"].clone();
+        assert_eq!(locked.width, "24");
+        assert_eq!(locked.options, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::api::IconifyIcon;
+use crate::monochrome::Monochrome;
+use crate::naming::IconIdentifier;
+
+/// Default manifest file name
+pub const MANIFEST_FILE: &str = "icons.toml";
+
+/// Declarative list of the icons vendored in a project (icons.toml)
+///
+/// ```toml
+/// api_urls = ["https://iconify.example.com", "https://api.iconify.design"]
+/// icons = ["mdi:home", "heroicons:arrow-left", "./assets/logo.svg", "./assets/icons/"]
+///
+/// [options."mdi:home"]
+/// width = 32
+/// height = 32
+///
+/// [options."my-icons"]
+/// monochrome = "auto"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Iconify API hosts, tried in order (default: the public Iconify API)
+    #[serde(default)]
+    pub api_urls: Vec<String>,
+    /// Icon identifiers, SVG file paths, or directory paths (same inputs as `add`)
+    #[serde(default)]
+    pub icons: Vec<String>,
+    /// Per-icon options, keyed by icon identifier (e.g., "mdi:home", "my-icons:logo"),
+    /// or per-collection options, keyed by collection (e.g., "my-icons")
+    #[serde(default)]
+    pub options: BTreeMap<String, IconOptions>,
+}
+
+/// Options applied to an icon when it is imported
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IconOptions {
+    /// Override the default width
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    /// Override the default height
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    /// Rewrite the colours of local icons to `currentColor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monochrome: Option<Monochrome>,
+}
+
+impl IconOptions {
+    /// Options set here, falling back to `defaults`
+    fn with_defaults(&self, defaults: &IconOptions) -> IconOptions {
+        IconOptions {
+            width: self.width.or(defaults.width),
+            height: self.height.or(defaults.height),
+            monochrome: self.monochrome.or(defaults.monochrome),
+        }
+    }
+}
+
+impl Manifest {
+    /// Load a manifest from a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read manifest {}", path.display()))?;
+
+        toml::from_str(&content).context(format!("Failed to parse manifest {}", path.display()))
+    }
+
+    /// The options declared for an icon, merged with those of its collection (if any)
+    pub fn icon_options(&self, identifier: &IconIdentifier) -> Option<IconOptions> {
+        match (
+            self.options.get(&identifier.full_name),
+            self.options.get(&identifier.collection),
+        ) {
+            (Some(icon), Some(collection)) => Some(icon.with_defaults(collection)),
+            (icon, collection) => icon.or(collection).cloned(),
+        }
+    }
+
+    /// Apply the size declared for an icon (if any)
+    pub fn apply_options(&self, identifier: &IconIdentifier, icon: &mut IconifyIcon) {
+        if let Some(options) = self.icon_options(identifier) {
+            // Resize the icon, not its content
+            icon.view_box = Some(icon.effective_view_box());
+            if let Some(width) = options.width {
+                icon.width = Some(width);
+            }
+            if let Some(height) = options.height {
+                icon.height = Some(height);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::IconOrigin;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_manifest() -> Result<()> {
+        let manifest: Manifest = toml::from_str(indoc! {r#"
+            icons = ["mdi:home", "./assets/icons/"]
+
+            [options."mdi:home"]
+            width = 32
+        "#})?;
+
+        assert_eq!(manifest.icons, vec!["mdi:home", "./assets/icons/"]);
+        assert_eq!(
+            manifest.options.get("mdi:home"),
+            Some(&IconOptions {
+                width: Some(32.0),
+                height: None,
+                monochrome: None,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_manifest_api_urls() -> Result<()> {
+        let manifest: Manifest = toml::from_str(indoc! {r#"
+            api_urls = ["http://localhost:3000", "https://api.iconify.design"]
+        "#})?;
+
+        assert_eq!(
+            manifest.api_urls,
+            vec!["http://localhost:3000", "https://api.iconify.design"]
+        );
+        assert!(manifest.icons.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_unknown_fields() {
+        let result: Result<Manifest, _> = toml::from_str(r#"icon = ["mdi:home"]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_apply_options() {
+        let mut manifest = Manifest::default();
+        manifest.options.insert(
+            "mdi:home".to_string(),
+            IconOptions {
+                width: Some(32.0),
+                height: None,
+                monochrome: None,
+            },
+        );
+
+        let mut icon = IconifyIcon {
+            body: "<path/>".to_string(),
+            left: None,
+            top: None,
+            width: Some(24.0),
+            height: Some(24.0),
+            view_box: Some("0 0 24 24".to_string()),
+            origin: IconOrigin::default(),
+        };
+
+        manifest.apply_options(&IconIdentifier::parse("mdi:settings").unwrap(), &mut icon);
+        assert_eq!(icon.width, Some(24.0));
+
+        manifest.apply_options(&IconIdentifier::parse("mdi:home").unwrap(), &mut icon);
+        assert_eq!(icon.width, Some(32.0));
+        assert_eq!(icon.height, Some(24.0));
+        assert_eq!(icon.view_box, Some("0 0 24 24".to_string()));
+    }
+
+    #[test]
+    fn test_icon_options_merge_collection() -> Result<()> {
+        let manifest: Manifest = toml::from_str(indoc! {r#"
+            [options."my-icons"]
+            monochrome = "auto"
+            width = 16
+
+            [options."my-icons:flag"]
+            monochrome = "force"
+        "#})?;
+
+        assert_eq!(
+            manifest.icon_options(&IconIdentifier::parse("my-icons:flag")?),
+            Some(IconOptions {
+                width: Some(16.0),
+                height: None,
+                monochrome: Some(Monochrome::Force),
+            })
+        );
+        assert_eq!(
+            manifest
+                .icon_options(&IconIdentifier::parse("my-icons:logo")?)
+                .and_then(|options| options.monochrome),
+            Some(Monochrome::Auto)
+        );
+        assert_eq!(
+            manifest.icon_options(&IconIdentifier::parse("mdi:home")?),
+            None
+        );
+
+        Ok(())
+    }
+}
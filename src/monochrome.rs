@@ -0,0 +1,374 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::api::{IconOrigin, IconifyIcon};
+use crate::svg::{self, Node};
+
+/// Attributes (and CSS properties) painting with a colour
+const COLOR_PROPERTIES: &[&str] = &[
+    "fill",
+    "stroke",
+    "stop-color",
+    "flood-color",
+    "lighting-color",
+    "color",
+];
+
+/// Elements whose content isn't painted with the icon colour (the colours of a mask are
+/// luminance, of a clip path are ignored, of a pattern are its own), kept as-is
+const UNPAINTED_ELEMENTS: &[&str] = &["mask", "clipPath", "pattern"];
+
+/// Paint values that aren't colours, kept as-is
+const NON_COLORS: &[&str] = &[
+    "none",
+    "transparent",
+    "currentcolor",
+    "inherit",
+    "initial",
+    "unset",
+    "context-fill",
+    "context-stroke",
+];
+
+/// Common colour keywords with their hex value, so that `black` and `#000` are one colour
+const NAMED_COLORS: &[(&str, &str)] = &[
+    ("black", "#000000"),
+    ("white", "#ffffff"),
+    ("red", "#ff0000"),
+    ("lime", "#00ff00"),
+    ("blue", "#0000ff"),
+];
+
+/// How the colours of local icons are rewritten to `currentColor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Monochrome {
+    /// Rewrite the colour of single-colour icons, keep multi-colour icons as-is
+    Auto,
+    /// Rewrite every colour, even in multi-colour icons
+    Force,
+}
+
+impl fmt::Display for Monochrome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Monochrome::Auto => write!(f, "auto"),
+            Monochrome::Force => write!(f, "force"),
+        }
+    }
+}
+
+/// Result of the colour normalization of an icon
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The colours found were rewritten to `currentColor`
+    Converted(Vec<String>),
+    /// No colour to rewrite (the icon already uses `currentColor`, or inherits its colour)
+    Unchanged,
+    /// Multi-colour icon kept as-is, with the colours found
+    MultiColor(Vec<String>),
+}
+
+/// Rewrite the colours of a local icon (IconifyJSON file, SVG file or archive) to
+/// `currentColor`, recording the mode in its origin so that `update` re-applies it
+///
+/// Returns `None` for icons of Iconify collections, which already use `currentColor`.
+pub fn normalize_icon(icon: &mut IconifyIcon, mode: Monochrome) -> Result<Option<Outcome>> {
+    let (IconOrigin::IconSet { monochrome, .. }
+    | IconOrigin::Svg { monochrome, .. }
+    | IconOrigin::Archive { monochrome, .. }) = &mut icon.origin
+    else {
+        return Ok(None);
+    };
+    *monochrome = Some(mode);
+
+    let wrapped = svg::wrap_body(&icon.body);
+    let doc = roxmltree::Document::parse(&wrapped).context("Failed to parse SVG body")?;
+    let mut nodes: Vec<Node> = doc
+        .root_element()
+        .children()
+        .filter_map(|node| Node::build(&node))
+        .collect();
+
+    let mut colors = BTreeSet::new();
+    collect_colors(&nodes, &mut colors);
+    let colors: Vec<String> = colors.into_iter().collect();
+
+    if colors.is_empty() {
+        return Ok(Some(Outcome::Unchanged));
+    }
+    if colors.len() > 1 && mode == Monochrome::Auto {
+        return Ok(Some(Outcome::MultiColor(colors)));
+    }
+
+    rewrite_colors(&mut nodes);
+    let mut body = String::new();
+    for node in &nodes {
+        node.write(&mut body);
+    }
+    icon.body = body;
+
+    Ok(Some(Outcome::Converted(colors)))
+}
+
+/// Collect the (normalized) colours painting a body, from its attributes and styles
+fn collect_colors(nodes: &[Node], colors: &mut BTreeSet<String>) {
+    for node in nodes {
+        let Node::Element(element) = node else {
+            continue;
+        };
+        if UNPAINTED_ELEMENTS.contains(&element.name.as_str()) {
+            continue;
+        }
+
+        for (name, value) in &element.attributes {
+            if name == "style" {
+                colors.extend(declaration_colors(value));
+            } else if COLOR_PROPERTIES.contains(&name.as_str()) {
+                colors.extend(paint_color(value));
+            }
+        }
+
+        if element.name == "style" {
+            for child in &element.children {
+                if let Node::Text(css) = child {
+                    for block in svg::css_declaration_blocks(css) {
+                        colors.extend(declaration_colors(&css[block]));
+                    }
+                }
+            }
+        }
+
+        collect_colors(&element.children, colors);
+    }
+}
+
+/// Replace every colour of a body by `currentColor`
+fn rewrite_colors(nodes: &mut [Node]) {
+    for node in nodes {
+        let Node::Element(element) = node else {
+            continue;
+        };
+        if UNPAINTED_ELEMENTS.contains(&element.name.as_str()) {
+            continue;
+        }
+
+        for (name, value) in &mut element.attributes {
+            if name == "style" {
+                *value = rewrite_declarations(value);
+            } else if COLOR_PROPERTIES.contains(&name.as_str()) && paint_color(value).is_some() {
+                *value = "currentColor".to_string();
+            }
+        }
+
+        if element.name == "style" {
+            for child in &mut element.children {
+                if let Node::Text(css) = child {
+                    *css = rewrite_stylesheet(css);
+                }
+            }
+        }
+
+        rewrite_colors(&mut element.children);
+    }
+}
+
+/// Normalize a paint value, or `None` when it isn't a colour (`none`, `url(#gradient)`...)
+/// Example: "#FFF" → "#ffffff", "black" → "#000000", "rgb(0, 0, 0)" → "rgb(0,0,0)"
+fn paint_color(value: &str) -> Option<String> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty()
+        || NON_COLORS.contains(&value.as_str())
+        || value.starts_with("url(")
+        || value.starts_with("var(")
+    {
+        return None;
+    }
+
+    if let Some(hex) = value.strip_prefix('#')
+        && matches!(hex.len(), 3 | 4)
+    {
+        return Some(hex.chars().fold("#".to_string(), |mut color, c| {
+            color.push(c);
+            color.push(c);
+            color
+        }));
+    }
+
+    if let Some((_, hex)) = NAMED_COLORS.iter().find(|(name, _)| *name == value) {
+        return Some(hex.to_string());
+    }
+
+    Some(value.split_whitespace().collect())
+}
+
+/// Colours of CSS declarations (e.g., "fill: #000; stroke-width: 2")
+fn declaration_colors(css: &str) -> impl Iterator<Item = String> + '_ {
+    css.split(';').filter_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        let property = property.trim().to_lowercase();
+        if COLOR_PROPERTIES.contains(&property.as_str()) {
+            paint_color(value)
+        } else {
+            None
+        }
+    })
+}
+
+/// Replace the colours of CSS declarations by `currentColor`
+fn rewrite_declarations(css: &str) -> String {
+    css.split(';')
+        .map(|declaration| match declaration.split_once(':') {
+            Some((property, value))
+                if COLOR_PROPERTIES.contains(&property.trim().to_lowercase().as_str())
+                    && paint_color(value).is_some() =>
+            {
+                // Keep the whitespace around the value
+                let before = &value[..value.len() - value.trim_start().len()];
+                let after = &value[value.trim_end().len()..];
+                format!("{}:{}currentColor{}", property, before, after)
+            }
+            _ => declaration.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Replace the colours of the declaration blocks of a stylesheet by `currentColor`
+fn rewrite_stylesheet(css: &str) -> String {
+    let mut rewritten = String::new();
+    let mut end = 0;
+    for block in svg::css_declaration_blocks(css) {
+        rewritten.push_str(&css[end..block.start]);
+        rewritten.push_str(&rewrite_declarations(&css[block.clone()]));
+        end = block.end;
+    }
+    rewritten.push_str(&css[end..]);
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn local_icon(body: &str) -> IconifyIcon {
+        IconifyIcon {
+            body: body.to_string(),
+            left: None,
+            top: None,
+            width: None,
+            height: None,
+            view_box: None,
+            origin: IconOrigin::Svg {
+                path: "logo.svg".into(),
+                symbol: None,
+                optimize: None,
+                monochrome: None,
+            },
+        }
+    }
+
+    #[rstest]
+    #[case("#000", Some("#000000"))]
+    #[case("#1E293B", Some("#1e293b"))]
+    #[case(" black ", Some("#000000"))]
+    #[case("rgb(0, 0, 0)", Some("rgb(0,0,0)"))]
+    #[case("none", None)]
+    #[case("currentColor", None)]
+    #[case("url(#gradient)", None)]
+    fn test_paint_color(#[case] value: &str, #[case] expected: Option<&str>) {
+        assert_eq!(paint_color(value).as_deref(), expected);
+    }
+
+    #[test]
+    fn test_rewrite_stylesheet() {
+        assert_eq!(
+            rewrite_stylesheet(
+                ".a { fill: #000; stroke-width: 2 } @media print { .b { stroke:red } }"
+            ),
+            ".a { fill: currentColor; stroke-width: 2 } @media print { .b { stroke:currentColor } }"
+        );
+        assert_eq!(
+            rewrite_declarations("fill:none;stroke:#1E293B"),
+            "fill:none;stroke:currentColor"
+        );
+    }
+
+    #[rstest]
+    #[case::fill(
+        Monochrome::Auto,
+        r##"<path fill="#000" d="M0 0h24v24H0z"/><circle fill="black" r="2"/>"##,
+        r#"<path fill="currentColor" d="M0 0h24v24H0z"/><circle fill="currentColor" r="2"/>"#,
+        Outcome::Converted(vec!["#000000".to_string()])
+    )]
+    #[case::stroke_keeps_none(
+        Monochrome::Auto,
+        r##"<g fill="none" stroke="#1E293B" style="stroke-width: 2"><path d="M4 4l16 16"/></g>"##,
+        r#"<g fill="none" stroke="currentColor" style="stroke-width: 2"><path d="M4 4l16 16"/></g>"#,
+        Outcome::Converted(vec!["#1e293b".to_string()])
+    )]
+    #[case::style(
+        Monochrome::Auto,
+        r##"<style>.a { fill: #333 }</style><path class="a" style="stroke:#333" d="M0 0"/>"##,
+        r#"<style>.a { fill: currentColor }</style><path class="a" style="stroke:currentColor" d="M0 0"/>"#,
+        Outcome::Converted(vec!["#333333".to_string()])
+    )]
+    #[case::masked(
+        Monochrome::Force,
+        r##"<mask id="m"><rect fill="#fff" width="24" height="24"/><circle fill="#000" r="4"/></mask><clipPath id="c"><path fill="red" d="M0 0"/></clipPath><path fill="#333" mask="url(#m)" clip-path="url(#c)" d="M1 1"/>"##,
+        r##"<mask id="m"><rect fill="#fff" width="24" height="24"/><circle fill="#000" r="4"/></mask><clipPath id="c"><path fill="red" d="M0 0"/></clipPath><path fill="currentColor" mask="url(#m)" clip-path="url(#c)" d="M1 1"/>"##,
+        Outcome::Converted(vec!["#333333".to_string()])
+    )]
+    #[case::masked_auto(
+        Monochrome::Auto,
+        r##"<mask id="m"><path fill="#fff" d="M0 0"/></mask><path fill="#333" mask="url(#m)" d="M1 1"/>"##,
+        r##"<mask id="m"><path fill="#fff" d="M0 0"/></mask><path fill="currentColor" mask="url(#m)" d="M1 1"/>"##,
+        Outcome::Converted(vec!["#333333".to_string()])
+    )]
+    #[case::multi_color(
+        Monochrome::Auto,
+        r##"<path fill="#f00" d="M0 0"/><path fill="#fff" d="M1 1"/>"##,
+        r##"<path fill="#f00" d="M0 0"/><path fill="#fff" d="M1 1"/>"##,
+        Outcome::MultiColor(vec!["#ff0000".to_string(), "#ffffff".to_string()])
+    )]
+    #[case::forced(
+        Monochrome::Force,
+        r##"<path fill="#f00" d="M0 0"/><path fill="#fff" d="M1 1"/>"##,
+        r#"<path fill="currentColor" d="M0 0"/><path fill="currentColor" d="M1 1"/>"#,
+        Outcome::Converted(vec!["#ff0000".to_string(), "#ffffff".to_string()])
+    )]
+    #[case::unchanged(
+        Monochrome::Auto,
+        r#"<path fill="currentColor" d="M0 0"/><path d="M1 1"/>"#,
+        r#"<path fill="currentColor" d="M0 0"/><path d="M1 1"/>"#,
+        Outcome::Unchanged
+    )]
+    fn test_normalize_icon(
+        #[case] mode: Monochrome,
+        #[case] body: &str,
+        #[case] expected_body: &str,
+        #[case] expected: Outcome,
+    ) -> Result<()> {
+        let mut icon = local_icon(body);
+        assert_eq!(normalize_icon(&mut icon, mode)?, Some(expected));
+        assert_eq!(icon.body, expected_body);
+        assert_eq!(icon.origin.monochrome(), Some(mode));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_iconify_icon() -> Result<()> {
+        let mut icon = IconifyIcon {
+            origin: IconOrigin::default(),
+            ..local_icon(r##"<path fill="#000" d="M0 0"/>"##)
+        };
+        assert_eq!(normalize_icon(&mut icon, Monochrome::Force)?, None);
+        assert_eq!(icon.body, r##"<path fill="#000" d="M0 0"/>"##);
+
+        Ok(())
+    }
+}
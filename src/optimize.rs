@@ -0,0 +1,556 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::api::{IconOrigin, IconifyIcon};
+use crate::svg::{self, Element, Node};
+
+/// Elements that don't render anything (editor and accessibility metadata)
+const METADATA_ELEMENTS: &[&str] = &["metadata", "title", "desc"];
+
+/// Metadata elements kept when they are referenced (accessible names and descriptions)
+const LABEL_ELEMENTS: &[&str] = &["title", "desc"];
+
+/// Presentation attributes with their initial value, removed by the aggressive preset
+/// when they have it (and no ancestor sets the property)
+const DEFAULT_VALUES: &[(&str, &str)] = &[
+    ("opacity", "1"),
+    ("fill-opacity", "1"),
+    ("fill-rule", "nonzero"),
+    ("clip-rule", "nonzero"),
+    ("stroke-opacity", "1"),
+    ("stroke-width", "1"),
+    ("stroke-linecap", "butt"),
+    ("stroke-linejoin", "miter"),
+    ("stroke-miterlimit", "4"),
+    ("stroke-dasharray", "none"),
+    ("stroke-dashoffset", "0"),
+    ("visibility", "visible"),
+];
+
+/// Attributes of a group that apply to the group as a whole, or identify it:
+/// such a group is never merged into its child
+const GROUP_ATTRIBUTES: &[&str] = &["id", "class", "style", "clip-path", "mask", "filter"];
+
+/// How much the body of local SVGs is optimized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// Strip metadata (but referenced titles and descriptions), unwrap and drop empty groups,
+    /// round path data to 3 decimals
+    Safe,
+    /// Also remove unreferenced IDs and default values, merge single-child groups into
+    /// their child, round path data to 2 decimals
+    Aggressive,
+}
+
+impl Preset {
+    /// Decimals kept in path data and point lists
+    fn precision(self) -> usize {
+        match self {
+            Preset::Safe => 3,
+            Preset::Aggressive => 2,
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Preset::Safe => write!(f, "safe"),
+            Preset::Aggressive => write!(f, "aggressive"),
+        }
+    }
+}
+
+/// Size of a body before and after optimization
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Report {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let saved = self.before.saturating_sub(self.after) * 100 / self.before.max(1);
+        write!(f, "{} → {} bytes (-{}%)", self.before, self.after, saved)
+    }
+}
+
+/// Optimize the body of a local icon (SVG file or archive), recording the preset in its
+/// origin so that `update` re-applies it
+///
+/// Returns `None` for icons of Iconify collections, which are already optimized.
+pub fn optimize_icon(icon: &mut IconifyIcon, preset: Preset) -> Result<Option<Report>> {
+    let (IconOrigin::Svg { optimize, .. } | IconOrigin::Archive { optimize, .. }) =
+        &mut icon.origin
+    else {
+        return Ok(None);
+    };
+    *optimize = Some(preset);
+
+    let before = icon.body.len();
+    icon.body = optimize_body(&icon.body, preset)?;
+
+    Ok(Some(Report {
+        before,
+        after: icon.body.len(),
+    }))
+}
+
+/// Optimize an SVG body (the content of the `<svg>` element)
+pub fn optimize_body(body: &str, preset: Preset) -> Result<String> {
+    let wrapped = svg::wrap_body(body);
+    let doc = roxmltree::Document::parse(&wrapped).context("Failed to parse SVG body")?;
+    let root = doc.root_element();
+
+    // IDs can be targeted by stylesheets, and their rules can set any property:
+    // keep all IDs and default values when there is one
+    let has_stylesheet = root.descendants().any(|node| node.has_tag_name("style"));
+
+    let optimizer = Optimizer {
+        preset,
+        referenced_ids: referenced_ids(&root),
+        keep_ids: has_stylesheet,
+        remove_defaults: preset == Preset::Aggressive && !has_stylesheet,
+    };
+    let nodes = optimizer.children(
+        root.children()
+            .filter_map(|node| Node::build(&node))
+            .collect(),
+        &HashSet::new(),
+    );
+
+    let mut optimized = String::new();
+    for node in &nodes {
+        node.write(&mut optimized);
+    }
+
+    Ok(optimized)
+}
+
+/// IDs referenced in a body (by `url(#id)`, `href="#id"` or ARIA relationships)
+fn referenced_ids(root: &roxmltree::Node) -> HashSet<String> {
+    let mut ids: HashSet<String> = svg::references(root).into_iter().collect();
+
+    for node in root.descendants() {
+        for name in ["aria-labelledby", "aria-describedby"] {
+            if let Some(value) = node.attribute(name) {
+                ids.extend(value.split_whitespace().map(str::to_string));
+            }
+        }
+    }
+
+    ids
+}
+
+struct Optimizer {
+    preset: Preset,
+    /// IDs referenced in the body
+    referenced_ids: HashSet<String>,
+    /// Whether unreferenced IDs are kept too (when a stylesheet could use them)
+    keep_ids: bool,
+    /// Whether attributes with their initial value are removed
+    /// (the `<svg>` of the Icon component can still set them, so only when aggressive)
+    remove_defaults: bool,
+}
+
+impl Optimizer {
+    /// Optimize sibling nodes, given the properties their ancestors set
+    fn children(&self, nodes: Vec<Node>, inherited: &HashSet<String>) -> Vec<Node> {
+        nodes
+            .into_iter()
+            .flat_map(|node| self.node(node, inherited))
+            .collect()
+    }
+
+    /// Optimize a node, which can be removed or replaced by its children
+    fn node(&self, node: Node, inherited: &HashSet<String>) -> Vec<Node> {
+        let Node::Element(mut element) = node else {
+            return vec![node];
+        };
+
+        // Titles and descriptions referenced by ARIA relationships are kept
+        if METADATA_ELEMENTS.contains(&element.name.as_str())
+            && !(LABEL_ELEMENTS.contains(&element.name.as_str())
+                && element
+                    .attribute("id")
+                    .is_some_and(|id| self.referenced_ids.contains(id)))
+        {
+            return Vec::new();
+        }
+
+        element
+            .attributes
+            .retain(|(name, value)| self.keep_attribute(name, value, inherited));
+        for (name, value) in &mut element.attributes {
+            match name.as_str() {
+                "d" => *value = round_numbers(value, self.preset.precision(), true),
+                "points" => *value = round_numbers(value, self.preset.precision(), false),
+                _ => {}
+            }
+        }
+
+        // Properties set as attributes or in the `style` attribute
+        let mut inherited = inherited.clone();
+        for (name, value) in &element.attributes {
+            if name == "style" {
+                inherited.extend(value.split(';').filter_map(|declaration| {
+                    let (property, _) = declaration.split_once(':')?;
+                    Some(property.trim().to_lowercase())
+                }));
+            } else {
+                inherited.insert(name.clone());
+            }
+        }
+        element.children = self.children(std::mem::take(&mut element.children), &inherited);
+
+        if element.name == "g" || element.name == "defs" {
+            if element.children.is_empty() {
+                return Vec::new();
+            }
+            if element.name == "g" && element.attributes.is_empty() {
+                return element.children;
+            }
+        }
+
+        if self.preset == Preset::Aggressive
+            && element.name == "g"
+            && let [Node::Element(child)] = element.children.as_mut_slice()
+            && merge_group(&element.attributes, child)
+        {
+            return element.children;
+        }
+
+        vec![Node::Element(element)]
+    }
+
+    fn keep_attribute(&self, name: &str, value: &str, inherited: &HashSet<String>) -> bool {
+        // Editor data (e.g., `data-name` of Illustrator layers)
+        if name.starts_with("data-") {
+            return false;
+        }
+
+        if name == "id" && self.preset == Preset::Aggressive && !self.keep_ids {
+            return self.referenced_ids.contains(value);
+        }
+
+        if !self.remove_defaults {
+            return true;
+        }
+        match DEFAULT_VALUES.iter().find(|(n, _)| *n == name) {
+            Some((_, default)) => value != *default || inherited.contains(name),
+            None => true,
+        }
+    }
+}
+
+/// Move the attributes of a group into its only child, when they don't conflict
+/// (transforms are combined, the group's one applying first)
+/// Returns false when the group must be kept
+fn merge_group(attributes: &[(String, String)], child: &mut Element) -> bool {
+    let conflicts = attributes.iter().any(|(name, _)| {
+        GROUP_ATTRIBUTES.contains(&name.as_str())
+            || (name != "transform" && child.attribute(name).is_some())
+    });
+    if conflicts {
+        return false;
+    }
+
+    let mut merged = attributes.to_vec();
+    for (name, value) in std::mem::take(&mut child.attributes) {
+        match merged.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, transform)) => *transform = format!("{} {}", transform, value),
+            None => merged.push((name, value)),
+        }
+    }
+    child.attributes = merged;
+
+    true
+}
+
+/// Round the numbers of path data (`path` is true) or of a point list
+fn round_numbers(data: &str, precision: usize, path: bool) -> String {
+    let mut rounded = String::with_capacity(data.len());
+    let mut rest = data;
+    let mut command = ' ';
+    // Index of the next parameter of the current path command
+    let mut parameter = 0;
+    // Whether the output ends with a number, which an unsigned number would extend
+    let mut after_number = false;
+
+    while let Some(c) = rest.chars().next() {
+        // Arc flags are single digits, possibly without separator (e.g., "a1 1 0 011 1")
+        let is_arc_flag = path
+            && matches!(command, 'a' | 'A')
+            && matches!(parameter % 7, 3 | 4)
+            && matches!(c, '0' | '1');
+
+        let length = if is_arc_flag { 0 } else { number_length(rest) };
+        if length > 0 {
+            let number = &rest[..length];
+            let formatted = number
+                .parse::<f64>()
+                .map(|n| format_number(n, precision))
+                .unwrap_or_else(|_| number.to_string());
+            if after_number && !formatted.starts_with('-') {
+                rounded.push(' ');
+            }
+            rounded.push_str(&formatted);
+            rest = &rest[length..];
+            parameter += 1;
+            after_number = true;
+            continue;
+        }
+
+        if is_arc_flag {
+            parameter += 1;
+        } else if path && c.is_ascii_alphabetic() {
+            command = c;
+            parameter = 0;
+        }
+        rounded.push(c);
+        rest = &rest[c.len_utf8()..];
+        after_number = false;
+    }
+
+    rounded
+}
+
+/// Length of the number at the start of `s` (sign, digits, decimals and exponent), 0 if none
+fn number_length(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    let integer_end = digits(i);
+    let mut end = integer_end;
+    if bytes.get(end) == Some(&b'.') {
+        end = digits(end + 1);
+    }
+    // At least one digit, before or after the dot
+    if end == i || (end == integer_end + 1 && integer_end == i) {
+        return 0;
+    }
+
+    i = end;
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        let exponent_start = i + 1 + usize::from(matches!(bytes.get(i + 1), Some(b'-' | b'+')));
+        let exponent_end = digits(exponent_start);
+        if exponent_end > exponent_start {
+            end = exponent_end;
+        }
+    }
+
+    end
+}
+
+/// Format a number with at most `precision` decimals, without leading or trailing zeros
+/// (e.g., "0.50" → ".5")
+fn format_number(number: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, number);
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+
+    match formatted {
+        "-0" => "0".to_string(),
+        _ if formatted.starts_with("0.") => formatted[1..].to_string(),
+        _ if formatted.starts_with("-0.") => format!("-{}", &formatted[2..]),
+        _ => formatted.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("M10.123456 20.5L3 4", 3, "M10.123 20.5L3 4")]
+    #[case("M1.00001-2.0004", 3, "M1-2")]
+    #[case("m1.5.5.25-0.25", 1, "m1.5 .5 .2-.2")]
+    #[case("M0 0a1 1 0 011 1", 3, "M0 0a1 1 0 011 1")]
+    #[case("M2e-5 1E3", 3, "M0 1000")]
+    #[case("M-0.0001 3h.12345", 2, "M0 3h.12")]
+    fn test_round_path_data(#[case] data: &str, #[case] precision: usize, #[case] expected: &str) {
+        assert_eq!(round_numbers(data, precision, true), expected);
+    }
+
+    #[test]
+    fn test_round_points() {
+        assert_eq!(
+            round_numbers("0.12345,1.5 2.9999,3", 2, false),
+            ".12,1.5 3,3"
+        );
+    }
+
+    #[rstest]
+    #[case("12", 2)]
+    #[case("-1.5e3x", 6)]
+    #[case(".5.5", 2)]
+    #[case("1.", 2)]
+    #[case("1e", 1)]
+    #[case("-.", 0)]
+    #[case("M1", 0)]
+    fn test_number_length(#[case] s: &str, #[case] expected: usize) {
+        assert_eq!(number_length(s), expected);
+    }
+
+    #[rstest]
+    // Metadata, editor data and comments
+    #[case(
+        Preset::Safe,
+        r#"<title>Logo</title><desc>Made with Figma</desc><!-- layer --><path data-name="Layer 1" d="M1 1"/>"#,
+        r#"<path d="M1 1"/>"#
+    )]
+    // Groups without attributes are unwrapped, empty groups and definitions removed
+    #[case(
+        Preset::Safe,
+        r#"<g><g fill="red"><path d="M1 1"/></g><g id="empty"/></g><defs/>"#,
+        r#"<g fill="red"><path d="M1 1"/></g>"#
+    )]
+    // Default values are kept, the Icon component can set them on the `<svg>`
+    #[case(
+        Preset::Safe,
+        r#"<path fill-opacity="1" stroke-width="1" fill-rule="nonzero" d="M1 1"/>"#,
+        r#"<path fill-opacity="1" stroke-width="1" fill-rule="nonzero" d="M1 1"/>"#
+    )]
+    // Default values, unless an ancestor sets the property (as attribute or style)
+    #[case(
+        Preset::Aggressive,
+        r#"<path fill-opacity="1" stroke-width="1" fill-rule="nonzero" d="M1 1"/><g fill-rule="evenodd"><path fill-rule="nonzero" d="M2 2"/></g>"#,
+        r#"<path d="M1 1"/><g fill-rule="evenodd"><path fill-rule="nonzero" d="M2 2"/></g>"#
+    )]
+    #[case(
+        Preset::Aggressive,
+        r#"<g style="stroke-width: 2"><path stroke-width="1" d="M1 1"/><path d="M2 2"/></g>"#,
+        r#"<g style="stroke-width: 2"><path stroke-width="1" d="M1 1"/><path d="M2 2"/></g>"#
+    )]
+    // or when a stylesheet could set it
+    #[case(
+        Preset::Aggressive,
+        r#"<style>path { stroke-width: 2 }</style><path class="a" stroke-width="1" d="M1 1"/>"#,
+        r#"<style>path { stroke-width: 2 }</style><path class="a" stroke-width="1" d="M1 1"/>"#
+    )]
+    // Titles and descriptions are kept when referenced
+    #[case(
+        Preset::Safe,
+        r#"<svg aria-labelledby="t" aria-describedby="d"><title id="t">Logo</title><desc id="d">A logo</desc><title>Unused</title><path d="M1 1"/></svg>"#,
+        r#"<svg aria-labelledby="t" aria-describedby="d"><title id="t">Logo</title><desc id="d">A logo</desc><path d="M1 1"/></svg>"#
+    )]
+    #[case(
+        Preset::Aggressive,
+        r#"<svg aria-labelledby="t"><title id="t">Logo</title><desc id="d">A logo</desc><path d="M1 1"/></svg>"#,
+        r#"<svg aria-labelledby="t"><title id="t">Logo</title><path d="M1 1"/></svg>"#
+    )]
+    #[case(
+        Preset::Safe,
+        r#"<path id="Vector" d="M1.123456 2"/>"#,
+        r#"<path id="Vector" d="M1.123 2"/>"#
+    )]
+    // Unreferenced IDs
+    #[case(
+        Preset::Aggressive,
+        r#"<path id="Vector" d="M1.123456 2"/><linearGradient id="a"/><path fill="url(#a)" d="M0 0"/>"#,
+        r#"<path d="M1.12 2"/><linearGradient id="a"/><path fill="url(#a)" d="M0 0"/>"#
+    )]
+    // Single-child groups are merged into their child
+    #[case(
+        Preset::Aggressive,
+        r#"<g transform="translate(1 1)" fill="red"><path transform="scale(2)" d="M0 0"/></g>"#,
+        r#"<path transform="translate(1 1) scale(2)" fill="red" d="M0 0"/>"#
+    )]
+    #[case(
+        Preset::Aggressive,
+        r#"<g fill="red"><path fill="blue" d="M0 0"/></g><g clip-path="url(#c)"><path d="M0 0"/></g><clipPath id="c"/>"#,
+        r#"<g fill="red"><path fill="blue" d="M0 0"/></g><g clip-path="url(#c)"><path d="M0 0"/></g><clipPath id="c"/>"#
+    )]
+    // IDs are kept when a stylesheet could use them
+    #[case(
+        Preset::Aggressive,
+        r#"<style>#dot { fill: red }</style><circle id="dot" r="1"/>"#,
+        r#"<style>#dot { fill: red }</style><circle id="dot" r="1"/>"#
+    )]
+    fn test_optimize_body(
+        #[case] preset: Preset,
+        #[case] body: &str,
+        #[case] expected: &str,
+    ) -> Result<()> {
+        assert_eq!(optimize_body(body, preset)?, expected);
+        // Optimizing again changes nothing
+        assert_eq!(optimize_body(expected, preset)?, expected);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        "tests/fixtures/editors/inkscape.svg",
+        Preset::Safe,
+        r#"<g id="layer1"><path style="fill:#000000;stroke-width:1.00000" d="M 12,2 15.09,8.26 22,9.27 17,14.14 18.18,21.02 12,17.77 5.82,21.02 7,14.14 2,9.27 8.91,8.26 Z" id="path1"/></g>"#
+    )]
+    #[case(
+        "tests/fixtures/editors/inkscape.svg",
+        Preset::Aggressive,
+        r#"<path style="fill:#000000;stroke-width:1.00000" d="M 12,2 15.09,8.26 22,9.27 17,14.14 18.18,21.02 12,17.77 5.82,21.02 7,14.14 2,9.27 8.91,8.26 Z"/>"#
+    )]
+    #[case(
+        "tests/fixtures/editors/illustrator.svg",
+        Preset::Safe,
+        r##"<path fill="#1E293B" fill-opacity="1" fill-rule="nonzero" stroke-width="1" d="M10,20v-6h4v6h5v-8h3L12,3L2,12h3v8H10z"/>"##
+    )]
+    #[case(
+        "tests/fixtures/editors/illustrator.svg",
+        Preset::Aggressive,
+        r##"<path fill="#1E293B" d="M10,20v-6h4v6h5v-8h3L12,3L2,12h3v8H10z"/>"##
+    )]
+    fn test_optimize_fixtures(
+        #[case] path: &str,
+        #[case] preset: Preset,
+        #[case] expected: &str,
+    ) -> Result<()> {
+        let icon = svg::parse_svg_file(std::path::Path::new(path))?;
+        assert_eq!(optimize_body(&icon.body, preset)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_icon() -> Result<()> {
+        let mut icon = IconifyIcon {
+            body: r#"<title>Logo</title><path d="M1.123456 2"/>"#.to_string(),
+            left: None,
+            top: None,
+            width: None,
+            height: None,
+            view_box: None,
+            origin: IconOrigin::Svg {
+                path: "logo.svg".into(),
+                symbol: None,
+                optimize: None,
+                monochrome: None,
+            },
+        };
+
+        let report = optimize_icon(&mut icon, Preset::Safe)?.unwrap();
+        assert_eq!(icon.body, r#"<path d="M1.123 2"/>"#);
+        assert_eq!(icon.origin.optimize(), Some(Preset::Safe));
+        assert_eq!(report.to_string(), "42 → 20 bytes (-52%)");
+
+        // Iconify icons are left as-is
+        let mut icon = IconifyIcon {
+            origin: IconOrigin::default(),
+            ..icon
+        };
+        assert_eq!(optimize_icon(&mut icon, Preset::Aggressive)?, None);
+
+        Ok(())
+    }
+}
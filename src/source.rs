@@ -0,0 +1,922 @@
+use anyhow::{Context, Result, anyhow};
+use futures_util::future::{FutureExt, LocalBoxFuture};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::api::{
+    FetchedIcons, IconNotFound, IconOrigin, IconifyClient, IconifyCollectionInfo, IconifyIcon,
+};
+use crate::archive::{self, Archive};
+use crate::iconify_json::{self, IconSets};
+use crate::naming::IconIdentifier;
+use crate::svg;
+
+/// A provider of icons (the Iconify API, local IconifyJSON files, local SVG files, ...)
+pub trait IconSource {
+    /// URI scheme selecting this source explicitly (e.g., `iconify` for `iconify:mdi:home`)
+    fn scheme(&self) -> &'static str;
+
+    /// Whether an input without scheme is for this source (e.g., an existing SVG path)
+    fn accepts(&self, input: &str) -> bool;
+
+    /// Resolve inputs (identifiers, paths, ... without the scheme) into icons
+    fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>>;
+
+    /// Metadata of a collection of this source (`None` when it has none, e.g., local SVGs)
+    fn collection_info<'a>(
+        &'a self,
+        _collection: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<IconifyCollectionInfo>>> {
+        async { Ok(None) }.boxed_local()
+    }
+}
+
+/// Icons resolved from inputs
+#[derive(Debug, Default)]
+pub struct Resolved {
+    pub icons: Vec<(IconIdentifier, IconifyIcon)>,
+    /// Inputs that don't exist in their source (e.g., icons removed upstream)
+    pub not_found: Vec<String>,
+    /// Inputs that could not be resolved (e.g., unreachable API, missing file)
+    pub failed: Vec<Failure>,
+    /// Collections with metadata, with the scheme of their source
+    pub collections: BTreeMap<String, &'static str>,
+}
+
+/// Inputs that could not be resolved, with the error (shared by the inputs of a collection)
+#[derive(Debug)]
+pub struct Failure {
+    /// What failed, e.g., a collection or a file
+    pub label: String,
+    pub inputs: Vec<String>,
+    pub error: anyhow::Error,
+}
+
+impl Resolved {
+    fn extend(&mut self, other: Resolved) {
+        self.icons.extend(other.icons);
+        self.not_found.extend(other.not_found);
+        self.failed.extend(other.failed);
+        self.collections.extend(other.collections);
+    }
+
+    /// Fail on the first input that could not be resolved or doesn't exist
+    pub fn check(self) -> Result<Self> {
+        if let Some(failure) = self.failed.into_iter().next() {
+            return Err(failure.error);
+        }
+
+        if let Some(input) = self.not_found.first() {
+            let identifier = IconIdentifier::parse(input)?;
+            return Err(anyhow::Error::new(IconNotFound {
+                collection: identifier.collection,
+                icon_name: identifier.icon_name,
+            })
+            .context(format!("Failed to fetch icon: {}", input)));
+        }
+
+        Ok(Self {
+            failed: Vec::new(),
+            ..self
+        })
+    }
+}
+
+/// The available icon sources, in the order they are tried for inputs without scheme
+pub struct IconSources {
+    sources: Vec<Box<dyn IconSource>>,
+}
+
+impl IconSources {
+    pub fn new(sources: Vec<Box<dyn IconSource>>) -> Self {
+        Self { sources }
+    }
+
+    /// Split the URI scheme of an input (e.g., `iconify:mdi:home`), if it is a known one
+    /// (existing paths are never split)
+    pub fn split_scheme<'a>(&self, input: &'a str) -> (Option<&dyn IconSource>, &'a str) {
+        if !Path::new(input).exists()
+            && let Some((scheme, rest)) = input.split_once(':')
+            && let Some(source) = self.find(scheme)
+        {
+            return (Some(source), rest);
+        }
+
+        (None, input)
+    }
+
+    fn find(&self, scheme: &str) -> Option<&dyn IconSource> {
+        self.sources
+            .iter()
+            .find(|source| source.scheme() == scheme)
+            .map(|source| source.as_ref())
+    }
+
+    /// Resolve inputs, each by the source of its scheme, or else by the first source accepting it
+    pub async fn resolve(&self, inputs: &[String]) -> Result<Resolved> {
+        let mut inputs_by_source = vec![Vec::new(); self.sources.len()];
+
+        for input in inputs {
+            let (source, rest) = self.split_scheme(input);
+            let index = match source {
+                Some(source) => self
+                    .sources
+                    .iter()
+                    .position(|s| s.scheme() == source.scheme()),
+                None => self.sources.iter().position(|s| s.accepts(input)),
+            }
+            .ok_or_else(|| anyhow!("No icon source for: {}", input))?;
+
+            inputs_by_source[index].push(rest.to_string());
+        }
+
+        let mut resolved = Resolved::default();
+        for (source, inputs) in self.sources.iter().zip(&inputs_by_source) {
+            if !inputs.is_empty() {
+                resolved.extend(source.resolve(inputs).await?);
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Fetch the metadata of collections (optional, failures are reported and skipped)
+    pub async fn collections_info(
+        &self,
+        collections: &BTreeMap<String, &'static str>,
+    ) -> HashMap<String, IconifyCollectionInfo> {
+        let mut collection_info = HashMap::new();
+
+        if collections.is_empty() {
+            return collection_info;
+        }
+
+        println!("\n📚 Fetching collection metadata...");
+        for (collection, scheme) in collections {
+            let Some(source) = self.find(scheme) else {
+                continue;
+            };
+
+            print!("  Fetching info for {}... ", collection);
+            match source.collection_info(collection).await {
+                Ok(Some(info)) => {
+                    println!("✓");
+                    collection_info.insert(collection.clone(), info);
+                }
+                Ok(None) => println!("⚠ (skipped: no metadata)"),
+                Err(e) => {
+                    println!("⚠ (skipped: {})", e);
+                    // Continue without collection info - it's optional
+                }
+            }
+        }
+
+        collection_info
+    }
+}
+
+/// Parse Iconify identifiers
+fn parse_identifiers(inputs: &[String]) -> Result<Vec<IconIdentifier>> {
+    inputs
+        .iter()
+        .map(|input| {
+            IconIdentifier::parse(input).context(format!("Invalid icon identifier: {}", input))
+        })
+        .collect()
+}
+
+/// Group icon names by collection
+fn group_by_collection(identifiers: &[IconIdentifier]) -> BTreeMap<String, Vec<String>> {
+    let mut names_by_collection: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for identifier in identifiers {
+        names_by_collection
+            .entry(identifier.collection.clone())
+            .or_default()
+            .push(identifier.icon_name.clone());
+    }
+
+    names_by_collection
+}
+
+/// Collect icons fetched per collection, in the order of the requested identifiers
+fn collect_fetched(
+    scheme: &'static str,
+    identifiers: Vec<IconIdentifier>,
+    results: Vec<(String, Result<FetchedIcons>)>,
+) -> Resolved {
+    let mut resolved = Resolved::default();
+    let mut fetched_by_collection = HashMap::new();
+
+    for (collection, result) in results {
+        match result {
+            Ok(fetched) => {
+                println!(
+                    "  {} ({} icon(s)) ✓ via {}",
+                    collection,
+                    fetched.icons.len(),
+                    fetched.source
+                );
+                fetched_by_collection.insert(collection, fetched);
+            }
+            Err(error) => {
+                let inputs = identifiers
+                    .iter()
+                    .filter(|identifier| identifier.collection == collection)
+                    .map(|identifier| identifier.full_name.clone())
+                    .collect();
+                resolved.failed.push(Failure {
+                    error: error.context(format!("Failed to fetch icons from {}", collection)),
+                    label: collection,
+                    inputs,
+                });
+            }
+        }
+    }
+
+    for identifier in identifiers {
+        let Some(fetched) = fetched_by_collection.get(&identifier.collection) else {
+            continue;
+        };
+
+        match fetched.icons.get(&identifier.icon_name) {
+            Some(icon) => {
+                resolved
+                    .collections
+                    .insert(identifier.collection.clone(), scheme);
+                resolved.icons.push((identifier, icon.clone()));
+            }
+            None => resolved.not_found.push(identifier.full_name),
+        }
+    }
+
+    resolved
+}
+
+/// Icons of the Iconify API (`iconify:mdi:home`), the fallback for any identifier
+impl IconSource for IconifyClient {
+    fn scheme(&self) -> &'static str {
+        "iconify"
+    }
+
+    fn accepts(&self, _input: &str) -> bool {
+        true
+    }
+
+    fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>> {
+        async move {
+            let identifiers = parse_identifiers(inputs)?;
+
+            // One batched request per collection, collections fetched concurrently
+            println!("📦 Fetching {} icon(s)...", identifiers.len());
+            let results = self
+                .fetch_collections(&group_by_collection(&identifiers))
+                .await;
+
+            Ok(collect_fetched(self.scheme(), identifiers, results))
+        }
+        .boxed_local()
+    }
+
+    fn collection_info<'a>(
+        &'a self,
+        collection: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<IconifyCollectionInfo>>> {
+        async move { self.fetch_collection_info(collection).await.map(Some) }.boxed_local()
+    }
+}
+
+/// Icons of local IconifyJSON files (`json:mdi:home`), used for the collections they have,
+/// and of custom IconifyJSON files (`./company-icons.json`, `./company-icons.json:logo`)
+impl IconSource for IconSets {
+    fn scheme(&self) -> &'static str {
+        "json"
+    }
+
+    fn accepts(&self, input: &str) -> bool {
+        iconify_json::split_file_input(input).is_some()
+            || IconIdentifier::parse(input)
+                .is_ok_and(|identifier| self.find(&identifier.collection).is_some())
+    }
+
+    fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>> {
+        async move {
+            let (file_inputs, inputs): (Vec<String>, Vec<String>) = inputs
+                .iter()
+                .cloned()
+                .partition(|input| iconify_json::split_file_input(input).is_some());
+
+            let mut resolved = Resolved::default();
+            if !file_inputs.is_empty() {
+                resolved.extend(self.resolve_files(&file_inputs));
+            }
+            if inputs.is_empty() {
+                return Ok(resolved);
+            }
+
+            let identifiers = parse_identifiers(&inputs)?;
+
+            println!(
+                "📦 Reading {} icon(s) from IconifyJSON files...",
+                identifiers.len()
+            );
+            let results = group_by_collection(&identifiers)
+                .into_iter()
+                .map(|(collection, icon_names)| {
+                    let result = match self.load(&collection) {
+                        Ok(Some((path, icon_set))) => {
+                            icon_set.fetch_icons(&icon_names, &path.display().to_string())
+                        }
+                        Ok(None) => Err(anyhow!("No IconifyJSON file for {}", collection)),
+                        Err(e) => Err(e),
+                    };
+                    (collection, result)
+                })
+                .collect();
+
+            resolved.extend(collect_fetched(self.scheme(), identifiers, results));
+            Ok(resolved)
+        }
+        .boxed_local()
+    }
+
+    fn collection_info<'a>(
+        &'a self,
+        collection: &'a str,
+    ) -> LocalBoxFuture<'a, Result<Option<IconifyCollectionInfo>>> {
+        async move {
+            match self.load(collection)? {
+                Some((path, icon_set)) => icon_set
+                    .info
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("no info in {}", path.display())),
+                None => Ok(None),
+            }
+        }
+        .boxed_local()
+    }
+}
+
+impl IconSets {
+    /// Resolve inputs of custom IconifyJSON files, reading each file once
+    fn resolve_files(&self, inputs: &[String]) -> Resolved {
+        // Icon names by file, `None` for all the icons of the file
+        let mut names_by_file: BTreeMap<&Path, Option<Vec<String>>> = BTreeMap::new();
+        for (path, name) in inputs
+            .iter()
+            .filter_map(|i| iconify_json::split_file_input(i))
+        {
+            let names = names_by_file
+                .entry(path)
+                .or_insert_with(|| Some(Vec::new()));
+            match (names, name) {
+                (Some(names), Some(name)) => names.push(name.to_string()),
+                (names, None) => *names = None,
+                (None, Some(_)) => {}
+            }
+        }
+
+        println!(
+            "📦 Reading {} IconifyJSON icon set file(s)...",
+            names_by_file.len()
+        );
+        let mut resolved = Resolved::default();
+        for (path, names) in names_by_file {
+            match self.read_file(path, names) {
+                Ok(file) => resolved.extend(file),
+                Err(error) => resolved.failed.push(Failure {
+                    label: path.display().to_string(),
+                    inputs: inputs
+                        .iter()
+                        .filter(|input| {
+                            iconify_json::split_file_input(input).is_some_and(|(p, _)| p == path)
+                        })
+                        .cloned()
+                        .collect(),
+                    error: error.context(format!("Failed to import {}", path.display())),
+                }),
+            }
+        }
+
+        resolved
+    }
+
+    /// Resolve icons of a custom IconifyJSON file (all of them when `names` is `None`),
+    /// its prefix being the collection
+    fn read_file(&self, path: &Path, names: Option<Vec<String>>) -> Result<Resolved> {
+        let icon_set = self.load_file(path)?;
+        let mut resolved = Resolved::default();
+
+        for name in names.unwrap_or_else(|| icon_set.icon_names()) {
+            let identifier = IconIdentifier::parse(&format!("{}:{}", icon_set.prefix, name))?;
+            match icon_set.resolve(&name)? {
+                Some(mut icon) => {
+                    icon.origin = IconOrigin::IconSet {
+                        path: path.to_path_buf(),
+                        last_modified: icon_set.last_modified,
+                        monochrome: None,
+                    };
+                    resolved.icons.push((identifier, icon));
+                }
+                None => resolved.not_found.push(identifier.full_name),
+            }
+        }
+
+        println!(
+            "  {} ({} icon(s)) ✓ via {}",
+            icon_set.prefix,
+            resolved.icons.len(),
+            path.display()
+        );
+        if !resolved.icons.is_empty() {
+            resolved.collections.insert(icon_set.prefix, self.scheme());
+        }
+
+        Ok(resolved)
+    }
+}
+
+/// SVG files of archives (`archive:./icons.tgz`, or any existing `.tgz`/`.tar.gz`/`.zip`)
+#[derive(Debug, Clone, Default)]
+pub struct Archives {
+    /// Only import the SVG files under this directory of the archives (e.g., `icons/`)
+    pub dir: Option<String>,
+    /// Collection of the imported icons (default: derived from the archive name)
+    pub collection: Option<String>,
+}
+
+impl IconSource for Archives {
+    fn scheme(&self) -> &'static str {
+        "archive"
+    }
+
+    fn accepts(&self, input: &str) -> bool {
+        let path = Path::new(input);
+        path.is_file() && archive::is_archive(path)
+    }
+
+    fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>> {
+        async move {
+            let mut resolved = Resolved::default();
+
+            println!("\n📦 Reading {} archive(s)...", inputs.len());
+            for input in inputs {
+                match self.read_archive(Path::new(input)) {
+                    Ok(icons) => resolved.icons.extend(icons),
+                    Err(error) => resolved.failed.push(Failure {
+                        label: input.clone(),
+                        inputs: vec![input.clone()],
+                        error,
+                    }),
+                }
+            }
+
+            Ok(resolved)
+        }
+        .boxed_local()
+    }
+}
+
+impl Archives {
+    /// Parse the SVG files of an archive (under `dir`), invalid ones are reported and skipped
+    fn read_archive(&self, path: &Path) -> Result<Vec<(IconIdentifier, IconifyIcon)>> {
+        let collection = match &self.collection {
+            Some(collection) => collection.clone(),
+            None => archive::collection_name(path)?,
+        };
+
+        let archive = Archive::read(path)?;
+        let svg_files = archive.scan(self.dir.as_deref());
+
+        if svg_files.is_empty() {
+            eprintln!(
+                "  ⚠ No SVG files found in {}{}",
+                path.display(),
+                self.dir
+                    .as_ref()
+                    .map(|dir| format!(" under {}", dir))
+                    .unwrap_or_default()
+            );
+        } else {
+            println!(
+                "  Found {} SVG(s) in {} ({})",
+                svg_files.len(),
+                path.display(),
+                archive.hash
+            );
+        }
+
+        let mut icons = Vec::new();
+        for (entry, icon_name) in svg_files {
+            let full_name = format!("{}:{}", collection, icon_name);
+            let origin = IconOrigin::Archive {
+                path: path.to_path_buf(),
+                entry: entry.to_string(),
+                archive_hash: archive.hash.clone(),
+                optimize: None,
+                monochrome: None,
+            };
+
+            match IconIdentifier::parse(&full_name) {
+                Ok(identifier) => match svg::parse_svg(&archive.svg_files[entry], origin) {
+                    Ok(icon) => icons.push((identifier, icon)),
+                    Err(e) => eprintln!("  ⚠ Skipping {}: {}", entry, e),
+                },
+                Err(e) => eprintln!("  ⚠ Invalid icon name {}: {}", full_name, e),
+            }
+        }
+
+        Ok(icons)
+    }
+}
+
+/// Local SVG files and directories (`file:./logo.svg`, or any existing path)
+#[derive(Debug, Clone, Default)]
+pub struct SvgFiles {
+    /// Split every SVG file into its `<symbol>`s (sprite sheets are otherwise detected)
+    pub sprite: bool,
+}
+
+impl IconSource for SvgFiles {
+    fn scheme(&self) -> &'static str {
+        "file"
+    }
+
+    fn accepts(&self, input: &str) -> bool {
+        Path::new(input).exists()
+    }
+
+    fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>> {
+        async move {
+            let mut resolved = Resolved::default();
+
+            // Classify inputs into files and directories
+            let mut svg_files = Vec::new();
+            let mut svg_directories = Vec::new();
+
+            for input in inputs {
+                let path = Path::new(input);
+
+                if !path.exists() {
+                    resolved.failed.push(Failure {
+                        label: input.clone(),
+                        inputs: vec![input.clone()],
+                        error: anyhow!("File not found: {}", input),
+                    });
+                } else if path.is_dir() {
+                    svg_directories.push(path);
+                } else if path.extension().and_then(|s| s.to_str()) == Some("svg") {
+                    svg_files.push(path);
+                } else {
+                    return Err(anyhow!(
+                        "Path exists but is not SVG file or directory: {}",
+                        input
+                    ));
+                }
+            }
+
+            // Process local SVG files
+            if !svg_files.is_empty() {
+                println!("\n📁 Processing {} local SVG file(s)...", svg_files.len());
+                for svg_path in svg_files {
+                    match self.process_svg_file(svg_path) {
+                        Ok(icons) => {
+                            for (identifier, _) in &icons {
+                                println!("  {} ✓", identifier.full_name);
+                            }
+                            resolved.icons.extend(icons);
+                        }
+                        Err(e) => {
+                            println!("  {} ✗", svg_path.display());
+                            resolved.failed.push(svg_failure(svg_path, e));
+                        }
+                    }
+                }
+            }
+
+            // Process SVG directories
+            if !svg_directories.is_empty() {
+                println!(
+                    "\n📂 Scanning {} director(ies) for SVGs...",
+                    svg_directories.len()
+                );
+                for dir_path in svg_directories {
+                    resolved.extend(scan_svg_directory(dir_path));
+                }
+            }
+
+            Ok(resolved)
+        }
+        .boxed_local()
+    }
+}
+
+impl SvgFiles {
+    /// Process an SVG file: a single icon, or one icon per symbol of a sprite sheet
+    fn process_svg_file(&self, svg_path: &Path) -> Result<Vec<(IconIdentifier, IconifyIcon)>> {
+        let collection = svg::extract_collection_name(
+            svg_path
+                .parent()
+                .ok_or_else(|| anyhow!("No parent directory for: {}", svg_path.display()))?,
+        )?;
+
+        if let Some(symbols) = svg::parse_sprite_file(svg_path, self.sprite)? {
+            println!(
+                "  Found {} symbol(s) in {}",
+                symbols.len(),
+                svg_path.display()
+            );
+
+            let mut icons = Vec::new();
+            for (symbol_id, icon) in symbols {
+                let full_name = format!("{}:{}", collection, symbol_id);
+                match IconIdentifier::parse(&full_name) {
+                    Ok(identifier) => icons.push((identifier, icon)),
+                    Err(e) => eprintln!("  ⚠ Invalid icon name {}: {}", full_name, e),
+                }
+            }
+            return Ok(icons);
+        }
+
+        let icon_name = svg_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid filename: {}", svg_path.display()))?
+            .to_string();
+
+        let full_name = format!("{}:{}", collection, icon_name);
+        let identifier = IconIdentifier::parse(&full_name)?;
+        let icon = svg::parse_svg_file(svg_path)?;
+
+        Ok(vec![(identifier, icon)])
+    }
+}
+
+/// A local file or directory that can't be read, failing the command
+/// (otherwise `sync` would remove the icons it still lists)
+fn svg_failure(path: &Path, error: anyhow::Error) -> Failure {
+    let input = path.display().to_string();
+    Failure {
+        label: input.clone(),
+        inputs: vec![input],
+        error: error.context(format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Parse the SVGs of a directory, invalid ones are reported as failures
+fn scan_svg_directory(dir_path: &Path) -> Resolved {
+    let mut resolved = Resolved::default();
+
+    let collection = match svg::extract_collection_name(dir_path) {
+        Ok(c) => c,
+        Err(e) => {
+            resolved.failed.push(svg_failure(dir_path, e));
+            return resolved;
+        }
+    };
+
+    let svg_files = match svg::scan_svg_directory(dir_path) {
+        Ok(files) => files,
+        Err(e) => {
+            resolved.failed.push(svg_failure(dir_path, e));
+            return resolved;
+        }
+    };
+
+    if !svg_files.is_empty() {
+        println!(
+            "  Found {} SVG(s) in {}",
+            svg_files.len(),
+            dir_path.display()
+        );
+    }
+
+    for (svg_path, icon_name) in svg_files {
+        let full_name = format!("{}:{}", collection, icon_name);
+
+        match IconIdentifier::parse(&full_name) {
+            Ok(identifier) => match svg::parse_svg_file(&svg_path) {
+                Ok(icon) => {
+                    resolved.icons.push((identifier, icon));
+                }
+                Err(e) => {
+                    println!("  {} ✗", svg_path.display());
+                    resolved.failed.push(svg_failure(&svg_path, e));
+                }
+            },
+            Err(e) => {
+                eprintln!("  ⚠ Invalid icon name {}: {}", full_name, e);
+            }
+        }
+    }
+
+    resolved
+}
+
+/// In-memory icon sources, to test the commands without network
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+
+    /// Icons of an in-memory collection, accepted for the collections it has
+    pub struct FakeSource {
+        pub scheme: &'static str,
+        /// Icons keyed by identifier (e.g., `mdi:home`)
+        pub icons: HashMap<String, IconifyIcon>,
+        /// Collections failing to resolve
+        pub broken: Vec<String>,
+    }
+
+    impl FakeSource {
+        pub fn new(scheme: &'static str, icons: &[(&str, &str)]) -> Self {
+            Self {
+                scheme,
+                icons: icons
+                    .iter()
+                    .map(|(name, body)| ((*name).to_string(), icon(body)))
+                    .collect(),
+                broken: Vec::new(),
+            }
+        }
+    }
+
+    pub fn icon(body: &str) -> IconifyIcon {
+        IconifyIcon {
+            body: body.to_string(),
+            left: None,
+            top: None,
+            width: None,
+            height: None,
+            view_box: None,
+            origin: IconOrigin::Iconify {
+                last_modified: None,
+            },
+        }
+    }
+
+    impl IconSource for FakeSource {
+        fn scheme(&self) -> &'static str {
+            self.scheme
+        }
+
+        fn accepts(&self, input: &str) -> bool {
+            IconIdentifier::parse(input).is_ok_and(|identifier| {
+                self.icons
+                    .keys()
+                    .any(|name| name.starts_with(&format!("{}:", identifier.collection)))
+            })
+        }
+
+        fn resolve<'a>(&'a self, inputs: &'a [String]) -> LocalBoxFuture<'a, Result<Resolved>> {
+            async move {
+                let identifiers = parse_identifiers(inputs)?;
+                let results = group_by_collection(&identifiers)
+                    .into_iter()
+                    .map(|(collection, icon_names)| {
+                        if self.broken.contains(&collection) {
+                            return (collection, Err(anyhow!("broken collection")));
+                        }
+
+                        let mut fetched = FetchedIcons {
+                            source: self.scheme.to_string(),
+                            ..Default::default()
+                        };
+                        for icon_name in icon_names {
+                            match self.icons.get(&format!("{}:{}", collection, icon_name)) {
+                                Some(icon) => {
+                                    fetched.icons.insert(icon_name, icon.clone());
+                                }
+                                None => fetched.not_found.push(icon_name),
+                            }
+                        }
+                        (collection, Ok(fetched))
+                    })
+                    .collect();
+
+                Ok(collect_fetched(self.scheme, identifiers, results))
+            }
+            .boxed_local()
+        }
+
+        fn collection_info<'a>(
+            &'a self,
+            collection: &'a str,
+        ) -> LocalBoxFuture<'a, Result<Option<IconifyCollectionInfo>>> {
+            async move {
+                Ok(Some(IconifyCollectionInfo {
+                    name: Some(format!("{} from {}", collection, self.scheme)),
+                    author: None,
+                    license: None,
+                    height: None,
+                    category: None,
+                    palette: None,
+                    total: None,
+                    version: None,
+                }))
+            }
+            .boxed_local()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::FakeSource;
+    use super::*;
+    use rstest::rstest;
+
+    fn sources() -> IconSources {
+        IconSources::new(vec![
+            Box::new(SvgFiles::default()),
+            Box::new(FakeSource::new("json", &[("local:home", "<path/>")])),
+            Box::new(FakeSource::new(
+                "iconify",
+                &[("mdi:home", "<rect/>"), ("local:home", "<circle/>")],
+            )),
+        ])
+    }
+
+    #[rstest]
+    #[case("iconify:mdi:home", Some("iconify"), "mdi:home")]
+    #[case("json:local:home", Some("json"), "local:home")]
+    #[case("file:./logo.svg", Some("file"), "./logo.svg")]
+    #[case("mdi:home", None, "mdi:home")]
+    #[case(
+        "tests/fixtures/test-icons/simple.svg",
+        None,
+        "tests/fixtures/test-icons/simple.svg"
+    )]
+    fn test_split_scheme(
+        #[case] input: &str,
+        #[case] expected_scheme: Option<&str>,
+        #[case] expected_rest: &str,
+    ) {
+        let sources = sources();
+        let (source, rest) = sources.split_scheme(input);
+        assert_eq!(source.map(|source| source.scheme()), expected_scheme);
+        assert_eq!(rest, expected_rest);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dispatch() -> Result<()> {
+        let sources = sources();
+        let inputs = [
+            "local:home",
+            "iconify:local:home",
+            "mdi:home",
+            "mdi:ghost",
+            "tests/fixtures/test-icons/simple.svg",
+        ]
+        .map(String::from);
+
+        let resolved = sources.resolve(&inputs).await?;
+
+        let bodies: Vec<(&str, &str)> = resolved
+            .icons
+            .iter()
+            .map(|(identifier, icon)| (identifier.full_name.as_str(), icon.body.as_str()))
+            .collect();
+        assert_eq!(bodies.len(), 4);
+        // Sources are tried in order: existing paths, then the collections they have
+        assert_eq!(bodies[0].0, "test-icons:simple");
+        assert!(bodies.contains(&("local:home", "<path/>")));
+        assert!(bodies.contains(&("local:home", "<circle/>")));
+        assert!(bodies.contains(&("mdi:home", "<rect/>")));
+
+        assert_eq!(resolved.not_found, vec!["mdi:ghost"]);
+        assert!(resolved.failed.is_empty());
+        // Local SVG collections have no metadata
+        assert_eq!(
+            resolved.collections.keys().collect::<Vec<_>>(),
+            vec!["local", "mdi"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_errors() -> Result<()> {
+        let mut broken = FakeSource::new("iconify", &[("mdi:home", "<rect/>")]);
+        broken.broken.push("mdi".to_string());
+        let sources = IconSources::new(vec![Box::new(SvgFiles::default()), Box::new(broken)]);
+
+        let resolved = sources
+            .resolve(&["mdi:home".to_string(), "file:missing.svg".to_string()])
+            .await?;
+        assert!(resolved.icons.is_empty());
+        assert_eq!(resolved.failed.len(), 2);
+        assert_eq!(resolved.failed[0].label, "missing.svg");
+        assert_eq!(resolved.failed[1].inputs, vec!["mdi:home"]);
+        assert!(resolved.check().is_err());
+
+        // Unknown icons are reported as not found
+        let sources = IconSources::new(vec![Box::new(FakeSource::new("iconify", &[]))]);
+        let err = sources
+            .resolve(&["iconify:mdi:ghost".to_string()])
+            .await?
+            .check()
+            .unwrap_err();
+        assert!(err.chain().any(|cause| cause.is::<IconNotFound>()));
+
+        Ok(())
+    }
+}
@@ -1,26 +1,52 @@
 use anyhow::{Context, Result, anyhow};
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use crate::api::IconifyIcon;
+use crate::api::{IconOrigin, IconifyIcon};
 
 /// Parse a single SVG file and extract icon data
 pub fn parse_svg_file(path: &Path) -> Result<IconifyIcon> {
     let content =
         fs::read_to_string(path).context(format!("Failed to read SVG file: {}", path.display()))?;
 
-    let doc = roxmltree::Document::parse(&content).context("Failed to parse SVG as XML")?;
+    parse_svg(
+        &content,
+        IconOrigin::Svg {
+            path: path.to_path_buf(),
+            symbol: None,
+            optimize: None,
+            monochrome: None,
+        },
+    )
+}
+
+/// Parse the content of an SVG file and extract icon data
+pub fn parse_svg(content: &str, origin: IconOrigin) -> Result<IconifyIcon> {
+    let doc = roxmltree::Document::parse(content).context("Failed to parse SVG as XML")?;
 
     let root = doc.root_element();
     if root.tag_name().name() != "svg" {
         return Err(anyhow!("Not a valid SVG file (root element is not <svg>)"));
     }
 
+    // Extract SVG body (inner content only, strip <svg> wrapper)
+    let body = extract_svg_body(&root)?;
+
+    icon_from_element(&root, body, origin)
+}
+
+/// Build an icon from the dimensions of its `<svg>` or `<symbol>` element
+fn icon_from_element(
+    element: &roxmltree::Node,
+    body: String,
+    origin: IconOrigin,
+) -> Result<IconifyIcon> {
     // Extract attributes
-    let width_attr = root.attribute("width");
-    let height_attr = root.attribute("height");
-    let viewbox_attr = root.attribute("viewBox");
+    let width_attr = element.attribute("width");
+    let height_attr = element.attribute("height");
+    let viewbox_attr = element.attribute("viewBox");
 
     // Parse dimension attributes, stripping units like "px", "em", etc.
     let width = width_attr.and_then(parse_dimension);
@@ -29,18 +55,241 @@ pub fn parse_svg_file(path: &Path) -> Result<IconifyIcon> {
 
     // Infer missing dimensions (following api.rs logic)
     let (final_width, final_height, final_viewbox) = infer_dimensions(width, height, view_box)?;
-
-    // Extract SVG body (inner content only, strip <svg> wrapper)
-    let body = extract_svg_body(&root)?;
+    let (left, top, _, _) = parse_viewbox(&final_viewbox)?;
 
     Ok(IconifyIcon {
-        body,
+        body: wrap_presentation_attributes(element, body),
+        left: Some(left),
+        top: Some(top),
         width: Some(final_width),
         height: Some(final_height),
         view_box: Some(final_viewbox),
+        origin,
     })
 }
 
+/// Inheritable presentation attributes of an `<svg>` (or `<symbol>`) element, that its
+/// content relies on (e.g., `fill="none" stroke="currentColor"` of stroke icons)
+const PRESENTATION_ATTRIBUTES: &[&str] = &[
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "clip-rule",
+    "stroke",
+    "stroke-width",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-opacity",
+    "opacity",
+    "color",
+    "paint-order",
+    "shape-rendering",
+    "font-family",
+    "font-size",
+    "font-style",
+    "font-weight",
+    "text-anchor",
+];
+
+/// Keep the presentation attributes of the root element by wrapping the body in a `<g>`
+/// that carries them (the `<svg>` element itself is rendered by the `Icon` component)
+fn wrap_presentation_attributes(element: &roxmltree::Node, body: String) -> String {
+    let attributes: String = element
+        .attributes()
+        .filter(|attr| attr.namespace().is_none() && PRESENTATION_ATTRIBUTES.contains(&attr.name()))
+        .map(|attr| format!(" {}=\"{}\"", attr.name(), escape_xml(attr.value())))
+        .collect();
+
+    if attributes.is_empty() || body.is_empty() {
+        body
+    } else {
+        format!("<g{}>{}</g>", attributes, body)
+    }
+}
+
+/// Namespaces of editor data (Inkscape, Illustrator, Sketch, ...) and of the RDF metadata
+/// they embed, dropped from icon bodies
+const EDITOR_NAMESPACES: &[&str] = &[
+    "http://www.inkscape.org/namespaces/inkscape",
+    "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd",
+    "http://ns.adobe.com/AdobeIllustrator/10.0/",
+    "http://ns.adobe.com/AdobeSVGViewerExtensions/3.0/",
+    "http://ns.adobe.com/Extensibility/1.0/",
+    "http://ns.adobe.com/Graphs/1.0/",
+    "http://ns.adobe.com/SaveForWeb/1.0/",
+    "http://ns.adobe.com/Variables/1.0/",
+    "http://www.bohemiancoding.com/sketch/ns",
+    "http://www.serif.com/",
+    "http://www.vector.evaxdesign.sk",
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+    "http://purl.org/dc/elements/1.1/",
+    "http://creativecommons.org/ns#",
+];
+
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+fn is_editor_namespace(namespace: Option<&str>) -> bool {
+    namespace.is_some_and(|namespace| EDITOR_NAMESPACES.contains(&namespace))
+}
+
+/// Elements that a sprite sheet has at its root, besides `<symbol>`s
+const SPRITE_ROOT_ELEMENTS: &[&str] = &["symbol", "defs", "style", "title", "desc", "metadata"];
+
+/// Parse the `<symbol>`s of a sprite sheet into icons keyed by symbol id
+///
+/// Returns `None` when the file isn't a sprite sheet, i.e., when its root has other content
+/// than symbols and shared definitions (unless `force` is set)
+pub fn parse_sprite_file(path: &Path, force: bool) -> Result<Option<Vec<(String, IconifyIcon)>>> {
+    let content =
+        fs::read_to_string(path).context(format!("Failed to read SVG file: {}", path.display()))?;
+
+    let doc = roxmltree::Document::parse(&content).context("Failed to parse SVG as XML")?;
+
+    let root = doc.root_element();
+    if root.tag_name().name() != "svg" {
+        return Err(anyhow!("Not a valid SVG file (root element is not <svg>)"));
+    }
+
+    let symbols: Vec<_> = root
+        .descendants()
+        .filter(|node| node.has_tag_name("symbol") && node.attribute("id").is_some())
+        .collect();
+
+    let only_definitions = root
+        .children()
+        .filter(|child| child.is_element())
+        .all(|child| SPRITE_ROOT_ELEMENTS.contains(&child.tag_name().name()));
+
+    if symbols.is_empty() {
+        return if force {
+            Err(anyhow!(
+                "No <symbol id=\"...\"> found in {}",
+                path.display()
+            ))
+        } else {
+            Ok(None)
+        };
+    }
+    if !only_definitions && !force {
+        return Ok(None);
+    }
+
+    let mut icons = Vec::new();
+    for symbol in symbols {
+        let id = symbol.attribute("id").unwrap_or_default();
+        let body = format!(
+            "{}{}",
+            shared_definitions(&root, &symbol),
+            extract_svg_body(&symbol)?
+        );
+
+        let icon = icon_from_element(
+            &symbol,
+            body,
+            IconOrigin::Svg {
+                path: path.to_path_buf(),
+                symbol: Some(id.to_string()),
+                optimize: None,
+                monochrome: None,
+            },
+        )
+        .context(format!("Invalid symbol '{}'", id))?;
+        icons.push((id.to_string(), icon));
+    }
+
+    Ok(Some(icons))
+}
+
+/// Parse a single `<symbol>` of a sprite sheet, returns `None` if it doesn't exist (anymore)
+pub fn parse_sprite_symbol(path: &Path, symbol_id: &str) -> Result<Option<IconifyIcon>> {
+    let icons = parse_sprite_file(path, true)?.unwrap_or_default();
+
+    Ok(icons
+        .into_iter()
+        .find(|(id, _)| id == symbol_id)
+        .map(|(_, icon)| icon))
+}
+
+/// The shared content used by a symbol, as a `<defs>` block: the elements it references
+/// (gradients, clip paths, other symbols, ...) and the document stylesheets
+fn shared_definitions(root: &roxmltree::Node, symbol: &roxmltree::Node) -> String {
+    let outside_symbol = |node: &roxmltree::Node| !node.ancestors().any(|a| a == *symbol);
+
+    // Elements referenced by the symbol, and by the elements it references
+    let mut pending = references(symbol);
+    let mut needed = Vec::new();
+    while let Some(id) = pending.pop() {
+        let Some(node) = root
+            .descendants()
+            .find(|node| node.attribute("id") == Some(id.as_str()) && outside_symbol(node))
+        else {
+            continue;
+        };
+        if !needed.contains(&node) {
+            needed.push(node);
+            pending.extend(references(&node));
+        }
+    }
+
+    // In document order, without the elements already included with an ancestor
+    let shared: Vec<String> = root
+        .descendants()
+        .filter(|node| {
+            (needed.contains(node) || (node.has_tag_name("style") && outside_symbol(node)))
+                && !node
+                    .ancestors()
+                    .skip(1)
+                    .any(|ancestor| needed.contains(&ancestor))
+        })
+        .filter_map(|node| node_to_xml(&node))
+        .collect();
+
+    if shared.is_empty() {
+        String::new()
+    } else {
+        format!("<defs>{}</defs>", shared.join(""))
+    }
+}
+
+/// IDs referenced in a subtree, by `url(#id)` (attributes and stylesheets) or `href="#id"`
+pub fn references(node: &roxmltree::Node) -> Vec<String> {
+    let mut ids = Vec::new();
+
+    for descendant in node.descendants() {
+        let mut values = Vec::new();
+        for attr in descendant.attributes() {
+            // Both `href` and `xlink:href`
+            if attr.name() == "href"
+                && let Some(id) = attr.value().strip_prefix('#')
+            {
+                ids.push(id.to_string());
+            }
+            values.push(attr.value());
+        }
+        if descendant.is_text() {
+            values.extend(descendant.text());
+        }
+
+        for value in values {
+            let mut rest = value;
+            while let Some(start) = rest.find("url(") {
+                rest = &rest[start + "url(".len()..];
+                let end = rest.find(')').unwrap_or(rest.len());
+                if let Some(id) = rest[..end].trim_matches(['\'', '"', ' ']).strip_prefix('#') {
+                    ids.push(id.to_string());
+                }
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    ids
+}
+
 /// Extract collection name from a directory path
 /// Example: "/path/to/my-icons" → "my-icons"
 pub fn extract_collection_name(path: &Path) -> Result<String> {
@@ -119,40 +368,34 @@ fn build_icon_name(base_path: &Path, svg_path: &Path) -> Result<String> {
 }
 
 /// Parse a dimension attribute, stripping units
-/// Examples: "24" → Some(24), "24px" → Some(24), "1.5em" → None, "100%" → None
-fn parse_dimension(attr: &str) -> Option<u32> {
-    // Try to parse as integer first
-    if let Ok(val) = attr.parse::<u32>() {
-        return Some(val);
-    }
+/// Examples: "24" → Some(24.0), "24px" → Some(24.0), "1.5em" → Some(1.5), "100%" → None
+fn parse_dimension(attr: &str) -> Option<f64> {
+    let trimmed = attr.trim();
 
     // Percentages are not supported
-    let trimmed = attr.trim();
     if trimmed.ends_with('%') {
         return None;
     }
 
-    // Try to strip common units and parse
-    for unit in &["px", "pt", "em", "rem", "vh", "vw"] {
-        if let Some(num_str) = trimmed.strip_suffix(unit)
-            && let Ok(val) = num_str.trim().parse::<f64>()
-        {
-            // Only accept integer values
-            if val.fract() == 0.0 && val > 0.0 {
-                return Some(val as u32);
-            }
-        }
-    }
-
-    None
+    // Strip common units
+    let number = ["px", "pt", "em", "rem", "vh", "vw"]
+        .iter()
+        .find_map(|unit| trimmed.strip_suffix(unit))
+        .unwrap_or(trimmed);
+
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|val| val.is_finite() && *val > 0.0)
 }
 
 /// Infer missing dimensions using API logic (api.rs:166-174)
 fn infer_dimensions(
-    width: Option<u32>,
-    height: Option<u32>,
+    width: Option<f64>,
+    height: Option<f64>,
     view_box: Option<String>,
-) -> Result<(u32, u32, String)> {
+) -> Result<(f64, f64, String)> {
     match (width, height, view_box) {
         // All present
         (Some(w), Some(h), Some(vb)) => Ok((w, h, vb)),
@@ -187,15 +430,18 @@ fn infer_dimensions(
         // Nothing - use default 24x24
         (None, None, None) => {
             eprintln!("  ⚠ No dimensions found, using default 24x24");
-            Ok((24, 24, "0 0 24 24".to_string()))
+            Ok((24.0, 24.0, "0 0 24 24".to_string()))
         }
     }
 }
 
 /// Parse viewBox attribute to extract dimensions
-/// Format: "minX minY width height"
-fn parse_viewbox(viewbox: &str) -> Result<(u32, u32, u32, u32)> {
-    let parts: Vec<&str> = viewbox.split_whitespace().collect();
+/// Format: "minX minY width height" (separated by spaces and/or commas)
+pub fn parse_viewbox(viewbox: &str) -> Result<(f64, f64, f64, f64)> {
+    let parts: Vec<&str> = viewbox
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .collect();
 
     if parts.len() != 4 {
         return Err(anyhow!(
@@ -209,13 +455,7 @@ fn parse_viewbox(viewbox: &str) -> Result<(u32, u32, u32, u32)> {
     let width = parts[2].parse::<f64>().context("Invalid viewBox width")?;
     let height = parts[3].parse::<f64>().context("Invalid viewBox height")?;
 
-    // Convert to u32, rounding if necessary
-    Ok((
-        min_x.round() as u32,
-        min_y.round() as u32,
-        width.round() as u32,
-        height.round() as u32,
-    ))
+    Ok((min_x, min_y, width, height))
 }
 
 /// Extract inner content from SVG element (strip <svg> wrapper)
@@ -240,38 +480,32 @@ fn extract_svg_body(svg_element: &roxmltree::Node) -> Result<String> {
 /// Convert XML node to string representation
 fn node_to_xml(node: &roxmltree::Node) -> Option<String> {
     match node.node_type() {
+        roxmltree::NodeType::Element if is_editor_namespace(node.tag_name().namespace()) => None,
         roxmltree::NodeType::Element => {
             let tag_name = node.tag_name().name();
             let mut xml = format!("<{}", tag_name);
 
-            // Add attributes
-            for attr in node.attributes() {
-                xml.push_str(&format!(
-                    " {}=\"{}\"",
-                    attr.name(),
-                    escape_xml(attr.value())
-                ));
+            // Add attributes (with their prefix, without editor data)
+            for (name, value) in node
+                .attributes()
+                .filter_map(|attr| Some((attribute_name(&attr)?, attr.value())))
+            {
+                xml.push_str(&format!(" {}=\"{}\"", name, escape_xml(value)));
             }
 
-            // Check if element has children
-            if node.has_children()
-                && node
-                    .children()
-                    .any(|c| !c.is_text() || !c.text().unwrap_or("").trim().is_empty())
-            {
-                xml.push('>');
+            // Children, without blank texts and editor data
+            let children: String = node
+                .children()
+                .filter_map(|child| node_to_xml(&child))
+                .collect();
 
-                // Add children
-                for child in node.children() {
-                    if let Some(child_xml) = node_to_xml(&child) {
-                        xml.push_str(&child_xml);
-                    }
-                }
-
-                xml.push_str(&format!("</{}>", tag_name));
-            } else {
+            if children.is_empty() {
                 // Self-closing tag
                 xml.push_str("/>");
+            } else {
+                xml.push('>');
+                xml.push_str(&children);
+                xml.push_str(&format!("</{}>", tag_name));
             }
 
             Some(xml)
@@ -279,7 +513,8 @@ fn node_to_xml(node: &roxmltree::Node) -> Option<String> {
         roxmltree::NodeType::Text => {
             let text = node.text()?;
             if !text.trim().is_empty() {
-                Some(escape_xml(text))
+                let parent = node.parent_element().map(|parent| parent.tag_name().name());
+                Some(text_to_xml(parent.unwrap_or_default(), text))
             } else {
                 None
             }
@@ -288,8 +523,47 @@ fn node_to_xml(node: &roxmltree::Node) -> Option<String> {
     }
 }
 
+/// Qualified name of an attribute in an icon body, or `None` to drop it: editor data,
+/// and namespaces that a body can't declare (only `xlink:` and `xml:` are known to browsers)
+fn attribute_name(attr: &roxmltree::Attribute) -> Option<String> {
+    match attr.namespace() {
+        None => Some(attr.name().to_string()),
+        Some(XLINK_NAMESPACE) => Some(format!("xlink:{}", attr.name())),
+        Some(XML_NAMESPACE) => Some(format!("xml:{}", attr.name())),
+        Some(_) => None,
+    }
+}
+
+/// Serialize a text, as a CDATA section in stylesheets that would need escaping
+/// (e.g., `.a > .b` selectors)
+fn text_to_xml(parent: &str, text: &str) -> String {
+    if parent == "style" && text.contains(['<', '>', '&']) {
+        format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+    } else {
+        escape_xml(text)
+    }
+}
+
+/// Ranges of the innermost `{ ... }` blocks of a stylesheet (the declarations of its rules)
+pub fn css_declaration_blocks(css: &str) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    for (index, c) in css.char_indices() {
+        match c {
+            '{' => start = Some(index + 1),
+            '}' => {
+                if let Some(start) = start.take() {
+                    blocks.push(start..index);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
 /// Escape XML special characters
-fn escape_xml(s: &str) -> String {
+pub fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -297,6 +571,90 @@ fn escape_xml(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+/// Wrap an icon body in an `<svg>` root declaring the namespaces bodies use, to parse it
+pub fn wrap_body(body: &str) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">{}</svg>"#,
+        body
+    )
+}
+
+/// An owned SVG node, for the passes rewriting icon bodies (optimization, colours)
+#[derive(Debug, Clone)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Node {
+    /// Copy an element or a non-blank text (other nodes are dropped, like comments and editor data)
+    pub fn build(node: &roxmltree::Node) -> Option<Self> {
+        match node.node_type() {
+            roxmltree::NodeType::Element if is_editor_namespace(node.tag_name().namespace()) => {
+                None
+            }
+            roxmltree::NodeType::Element => Some(Node::Element(Element {
+                name: node.tag_name().name().to_string(),
+                attributes: node
+                    .attributes()
+                    .filter_map(|attr| Some((attribute_name(&attr)?, attr.value().to_string())))
+                    .collect(),
+                children: node
+                    .children()
+                    .filter_map(|child| Node::build(&child))
+                    .collect(),
+            })),
+            roxmltree::NodeType::Text => node
+                .text()
+                .filter(|text| !text.trim().is_empty())
+                .map(|text| Node::Text(text.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Serialize the node (same output as `node_to_xml` for an unchanged node)
+    pub fn write(&self, xml: &mut String) {
+        match self {
+            Node::Text(text) => xml.push_str(&escape_xml(text)),
+            Node::Element(element) => {
+                xml.push_str(&format!("<{}", element.name));
+                for (name, value) in &element.attributes {
+                    xml.push_str(&format!(" {}=\"{}\"", name, escape_xml(value)));
+                }
+
+                if element.children.is_empty() {
+                    xml.push_str("/>");
+                } else {
+                    xml.push('>');
+                    for child in &element.children {
+                        match child {
+                            Node::Text(text) => xml.push_str(&text_to_xml(&element.name, text)),
+                            child => child.write(xml),
+                        }
+                    }
+                    xml.push_str(&format!("</{}>", element.name));
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,22 +664,27 @@ mod tests {
 
     #[test]
     fn test_parse_dimension() {
-        assert_eq!(parse_dimension("24"), Some(24));
-        assert_eq!(parse_dimension("100"), Some(100));
-        assert_eq!(parse_dimension("24px"), Some(24));
-        assert_eq!(parse_dimension("16pt"), Some(16));
-        assert_eq!(parse_dimension("1.5em"), None); // Non-integer
+        assert_eq!(parse_dimension("24"), Some(24.0));
+        assert_eq!(parse_dimension("100"), Some(100.0));
+        assert_eq!(parse_dimension("24px"), Some(24.0));
+        assert_eq!(parse_dimension("16pt"), Some(16.0));
+        assert_eq!(parse_dimension("20.5"), Some(20.5));
+        assert_eq!(parse_dimension("1.5em"), Some(1.5));
         assert_eq!(parse_dimension("100%"), None); // Percentage not supported
+        assert_eq!(parse_dimension("0"), None);
         assert_eq!(parse_dimension("invalid"), None);
     }
 
     #[test]
     fn test_parse_viewbox() {
         let result = parse_viewbox("0 0 24 24").unwrap();
-        assert_eq!(result, (0, 0, 24, 24));
+        assert_eq!(result, (0.0, 0.0, 24.0, 24.0));
 
         let result = parse_viewbox("0 0 100 50").unwrap();
-        assert_eq!(result, (0, 0, 100, 50));
+        assert_eq!(result, (0.0, 0.0, 100.0, 50.0));
+
+        let result = parse_viewbox("-2,0.5, 20.5 21").unwrap();
+        assert_eq!(result, (-2.0, 0.5, 20.5, 21.0));
 
         assert!(parse_viewbox("invalid").is_err());
         assert!(parse_viewbox("0 0 24").is_err()); // Only 3 values
@@ -330,19 +693,19 @@ mod tests {
     #[test]
     fn test_infer_dimensions_all_present() {
         let (w, h, vb) =
-            infer_dimensions(Some(24), Some(24), Some("0 0 24 24".to_string())).unwrap();
+            infer_dimensions(Some(24.0), Some(24.0), Some("0 0 24 24".to_string())).unwrap();
 
-        assert_eq!(w, 24);
-        assert_eq!(h, 24);
+        assert_eq!(w, 24.0);
+        assert_eq!(h, 24.0);
         assert_eq!(vb, "0 0 24 24");
     }
 
     #[test]
     fn test_infer_dimensions_only_width_height() {
-        let (w, h, vb) = infer_dimensions(Some(32), Some(32), None).unwrap();
+        let (w, h, vb) = infer_dimensions(Some(32.0), Some(32.0), None).unwrap();
 
-        assert_eq!(w, 32);
-        assert_eq!(h, 32);
+        assert_eq!(w, 32.0);
+        assert_eq!(h, 32.0);
         assert_eq!(vb, "0 0 32 32");
     }
 
@@ -350,8 +713,8 @@ mod tests {
     fn test_infer_dimensions_only_viewbox() {
         let (w, h, vb) = infer_dimensions(None, None, Some("0 0 48 48".to_string())).unwrap();
 
-        assert_eq!(w, 48);
-        assert_eq!(h, 48);
+        assert_eq!(w, 48.0);
+        assert_eq!(h, 48.0);
         assert_eq!(vb, "0 0 48 48");
     }
 
@@ -359,8 +722,8 @@ mod tests {
     fn test_infer_dimensions_defaults() {
         let (w, h, vb) = infer_dimensions(None, None, None).unwrap();
 
-        assert_eq!(w, 24);
-        assert_eq!(h, 24);
+        assert_eq!(w, 24.0);
+        assert_eq!(h, 24.0);
         assert_eq!(vb, "0 0 24 24");
     }
 
@@ -400,8 +763,8 @@ mod tests {
 
         let icon = parse_svg_file(&svg_path)?;
 
-        assert_eq!(icon.width, Some(24));
-        assert_eq!(icon.height, Some(24));
+        assert_eq!(icon.width, Some(24.0));
+        assert_eq!(icon.height, Some(24.0));
         assert_eq!(icon.view_box, Some("0 0 24 24".to_string()));
         assert!(icon.body.contains("path"));
         assert!(!icon.body.contains("<svg")); // Should not include svg wrapper
@@ -422,8 +785,8 @@ mod tests {
 
         let icon = parse_svg_file(&svg_path)?;
 
-        assert_eq!(icon.width, Some(48));
-        assert_eq!(icon.height, Some(48));
+        assert_eq!(icon.width, Some(48.0));
+        assert_eq!(icon.height, Some(48.0));
         assert_eq!(icon.view_box, Some("0 0 48 48".to_string()));
         assert!(icon.body.contains("circle"));
 
@@ -444,13 +807,35 @@ mod tests {
         let icon = parse_svg_file(&svg_path)?;
 
         // Should default to 24x24
-        assert_eq!(icon.width, Some(24));
-        assert_eq!(icon.height, Some(24));
+        assert_eq!(icon.width, Some(24.0));
+        assert_eq!(icon.height, Some(24.0));
         assert_eq!(icon.view_box, Some("0 0 24 24".to_string()));
 
         Ok(())
     }
 
+    #[test]
+    fn test_keep_root_presentation_attributes() -> Result<()> {
+        // Lucide-style stroke icon
+        let icon = parse_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" class="lucide"><path d="M5 12h14"/></svg>"#,
+            IconOrigin::default(),
+        )?;
+        assert_eq!(
+            icon.body,
+            r#"<g fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round"><path d="M5 12h14"/></g>"#
+        );
+
+        // Without presentation attributes, the body is left unwrapped
+        let icon = parse_svg(
+            r#"<svg viewBox="0 0 24 24" class="icon"><path d="M5 12h14"/></svg>"#,
+            IconOrigin::default(),
+        )?;
+        assert_eq!(icon.body, r#"<path d="M5 12h14"/>"#);
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_invalid_xml() {
         let temp_dir = TempDir::new().unwrap();
@@ -487,13 +872,19 @@ mod tests {
     }
 
     #[rstest]
-    #[case("tests/fixtures/test-icons/simple.svg", 24, 24, "0 0 24 24")]
-    #[case("tests/fixtures/test-icons/viewbox-only.svg", 48, 48, "0 0 48 48")]
-    #[case("tests/fixtures/test-icons/no-dimensions.svg", 24, 24, "0 0 24 24")]
+    #[case("tests/fixtures/test-icons/simple.svg", 24.0, 24.0, "0 0 24 24")]
+    #[case("tests/fixtures/test-icons/viewbox-only.svg", 48.0, 48.0, "0 0 48 48")]
+    #[case("tests/fixtures/test-icons/no-dimensions.svg", 24.0, 24.0, "0 0 24 24")]
+    #[case(
+        "tests/fixtures/test-icons/offset.svg",
+        20.5,
+        21.0,
+        "0.5 -1.25 20.5 21"
+    )]
     fn test_parse_svg_fixtures(
         #[case] path: &str,
-        #[case] expected_width: u32,
-        #[case] expected_height: u32,
+        #[case] expected_width: f64,
+        #[case] expected_height: f64,
         #[case] expected_viewbox: &str,
     ) -> Result<()> {
         let icon = parse_svg_file(Path::new(path))?;
@@ -501,25 +892,161 @@ mod tests {
         assert_eq!(icon.width, Some(expected_width));
         assert_eq!(icon.height, Some(expected_height));
         assert_eq!(icon.view_box, Some(expected_viewbox.to_string()));
+        assert_eq!(icon.effective_view_box(), expected_viewbox);
         assert!(!icon.body.is_empty());
         assert!(!icon.body.contains("<svg")); // Body should not include svg wrapper
 
         Ok(())
     }
 
+    #[test]
+    fn test_drop_editor_namespaces() -> Result<()> {
+        let icon = parse_svg_file(Path::new("tests/fixtures/editors/inkscape.svg"))?;
+
+        assert!(
+            icon.body.starts_with(
+                r#"<defs id="defs2"/><metadata id="metadata5"/><g id="layer1"><path "#
+            )
+        );
+        for editor_data in [
+            "namedview",
+            "nodetypes",
+            "connector-curvature",
+            "label",
+            "RDF",
+        ] {
+            assert!(
+                !icon.body.contains(editor_data),
+                "{} in {}",
+                editor_data,
+                icon.body
+            );
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        "xlink.svg",
+        r##"<defs><path id="dot" d="M12 10a2 2 0 1 0 0 4a2 2 0 1 0 0-4z"/></defs><use xlink:href="#dot" x="-6"/><use xlink:href="#dot"/><use href="#dot" x="6"/>"##
+    )]
+    #[case(
+        "xml-space.svg",
+        r#"<text x="2" y="16" xml:space="preserve" xml:lang="en">A  B</text>"#
+    )]
+    #[case(
+        "cdata-style.svg",
+        "<style><![CDATA[\n    g > .accent { fill: #f97316 }\n  ]]></style><g><circle class=\"accent\" cx=\"12\" cy=\"12\" r=\"10\"/></g>"
+    )]
+    #[case("foreign.svg", r#"<path d="M4 4h16v16H4z"/>"#)]
+    fn test_namespaced_fixtures(#[case] name: &str, #[case] expected: &str) -> Result<()> {
+        let icon = parse_svg_file(&Path::new("tests/fixtures/namespaces").join(name))?;
+        assert_eq!(icon.body, expected);
+
+        // Bodies parse again, and the owned nodes serialize them the same way
+        let wrapped = wrap_body(&icon.body);
+        let doc = roxmltree::Document::parse(&wrapped)?;
+        let mut xml = String::new();
+        for node in doc
+            .root_element()
+            .children()
+            .filter_map(|node| Node::build(&node))
+        {
+            node.write(&mut xml);
+        }
+        assert_eq!(xml, expected);
+
+        Ok(())
+    }
+
     #[test]
     fn test_scan_fixtures_directory() -> Result<()> {
         let results = scan_svg_directory(Path::new("tests/fixtures/test-icons"))?;
 
-        assert_eq!(results.len(), 5); // simple, viewbox-only, no-dimensions, arrows/left, arrows/right
+        assert_eq!(results.len(), 6); // simple, viewbox-only, no-dimensions, offset, arrows/left, arrows/right
 
         let names: Vec<String> = results.iter().map(|(_, name)| name.clone()).collect();
         assert!(names.contains(&"simple".to_string()));
         assert!(names.contains(&"viewbox-only".to_string()));
         assert!(names.contains(&"no-dimensions".to_string()));
+        assert!(names.contains(&"offset".to_string()));
         assert!(names.contains(&"arrows-left".to_string()));
         assert!(names.contains(&"arrows-right".to_string()));
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_sprite_file() -> Result<()> {
+        let path = Path::new("tests/fixtures/sprites/sprite.svg");
+        let icons = parse_sprite_file(path, false)?.expect("Sprite sheet should be detected");
+
+        let ids: Vec<&str> = icons.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["home", "dot", "dots"]);
+
+        let (_, home) = &icons[0];
+        assert_eq!(home.effective_view_box(), "0 0 24 24");
+        assert_eq!(
+            home.origin,
+            IconOrigin::Svg {
+                path: path.to_path_buf(),
+                symbol: Some("home".to_string()),
+                optimize: None,
+                monochrome: None,
+            }
+        );
+        // Referenced definitions are carried along (transitively), unused ones are not
+        assert!(
+            home.body
+                .starts_with("<defs><style>.accent { opacity: 0.5 }</style>")
+        );
+        assert!(home.body.contains(r#"<linearGradient id="fade">"#));
+        assert!(home.body.contains(r#"<linearGradient id="fade-vertical""#));
+        assert!(!home.body.contains("unused"));
+        assert!(home.body.ends_with(r#"fill="url(#fade-vertical)"/>"#));
+
+        // Symbols used by other symbols are carried along too
+        let (_, dots) = &icons[2];
+        assert_eq!(dots.effective_view_box(), "-1 -1 34 18");
+        assert_eq!(dots.width, Some(34.0));
+        assert!(
+            dots.body
+                .contains(r#"<symbol id="dot" viewBox="0 0 16 16">"#)
+        );
+        assert!(dots.body.contains(r##"<use xlink:href="#dot" x="16"/>"##));
+        assert!(!dots.body.contains("linearGradient"));
+
+        assert!(parse_sprite_symbol(path, "dot")?.is_some());
+        assert!(parse_sprite_symbol(path, "ghost")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sprite_detection() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+
+        // An icon using its own symbol is not a sprite sheet
+        let icon_path = temp_dir.path().join("icon.svg");
+        fs::write(
+            &icon_path,
+            r##"<svg viewBox="0 0 24 24"><symbol id="s"><path d="M1 1"/></symbol><use href="#s"/></svg>"##,
+        )?;
+        assert!(parse_sprite_file(&icon_path, false)?.is_none());
+        assert_eq!(
+            parse_sprite_file(&icon_path, true)?.map(|s| s.len()),
+            Some(1)
+        );
+
+        // Forcing a file without symbols is an error
+        assert!(
+            parse_sprite_file(Path::new("tests/fixtures/test-icons/simple.svg"), true).is_err()
+        );
+        assert!(
+            parse_sprite_file(Path::new("tests/fixtures/test-icons/simple.svg"), false)?.is_none()
+        );
+
+        Ok(())
+    }
 }
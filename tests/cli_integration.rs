@@ -10,6 +10,41 @@ fn cmd() -> Command {
     assert_cmd::cargo_bin_cmd!("dioxus-iconify")
 }
 
+/// Start a local stand-in for the Iconify API, answering with `handler(request_target)`
+/// Returns its base URL
+fn serve_api(handler: impl Fn(&str) -> (u16, String) + Send + 'static) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // Consume headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let target = request_line.split(' ').nth(1).unwrap_or_default();
+            let (status, body) = handler(target);
+            write!(
+                stream,
+                "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    base_url
+}
+
 #[test]
 fn test_cli_init_creates_mod_file() -> Result<()> {
     let temp_dir = TempDir::new()?;
@@ -237,94 +272,1356 @@ fn test_cli_skip_existing_flag() -> Result<()> {
 }
 
 #[test]
-#[ignore] // Requires internet connection and takes time to compile
-fn test_generated_code_compiles() -> Result<()> {
-    // Create a temporary directory for our test project
+fn test_cli_remove_icons() -> Result<()> {
     let temp_dir = TempDir::new()?;
-    let project_dir = temp_dir.path();
-    let icons_dir = project_dir.join("src/icons");
+    let output_dir = temp_dir.path().join("icons");
+
+    let test_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons/arrows");
 
-    // Add some test icons using the CLI
     cmd()
         .arg("add")
-        .arg("mdi:home")
-        .arg("heroicons:arrow-left")
-        .arg("lucide:settings")
+        .arg(&test_dir)
         .arg("--output")
-        .arg(&icons_dir)
+        .arg(&output_dir)
         .assert()
         .success();
 
-    // Create a minimal Cargo.toml for the test project
-    let cargo_toml = r#"[package]
-name = "icon-test"
-version = "0.1.0"
-edition = "2021"
+    // Remove a single icon
+    cmd()
+        .arg("remove")
+        .arg("arrows:left")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("arrows:left"));
 
-[dependencies]
-dioxus = "0.7"
-"#;
-    fs::write(project_dir.join("Cargo.toml"), cargo_toml)?;
+    let content = fs::read_to_string(output_dir.join("arrows.rs"))?;
+    assert!(!content.contains("pub const Left: IconData"));
+    assert!(content.contains("pub const Right: IconData"));
 
-    // Create a main.rs that uses the generated icons
-    let main_rs = r#"#![deny(warnings)]
+    // Remove the whole collection
+    cmd()
+        .arg("remove")
+        .arg("arrows:*")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
 
-mod icons;
+    assert!(
+        !output_dir.join("arrows.rs").exists(),
+        "arrows.rs should be deleted"
+    );
+    let mod_content = fs::read_to_string(output_dir.join("mod.rs"))?;
+    assert!(
+        !mod_content.contains("mod arrows;"),
+        "arrows module should be dropped from mod.rs"
+    );
 
-use dioxus::prelude::*;
-use icons::Icon;
-use icons::{heroicons, lucide, mdi};
+    Ok(())
+}
 
-fn main() {
-    // Use the icons to avoid dead_code warnings
-    let _home = mdi::Home;
-    let _arrow = heroicons::ArrowLeft;
-    let _settings = lucide::Settings;
+#[test]
+fn test_cli_sync_with_manifest() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let manifest_path = temp_dir.path().join("icons.toml");
 
-    println!("Icons loaded successfully");
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons");
+    let arrows_dir = fixtures.join("arrows");
+    let simple_svg = fixtures.join("simple.svg");
+
+    fs::write(
+        &manifest_path,
+        format!(
+            "icons = [{:?}, {:?}]\n\n[options.\"arrows:left\"]\nwidth = 32\n",
+            arrows_dir.display().to_string(),
+            simple_svg.display().to_string()
+        ),
+    )?;
+
+    cmd()
+        .arg("sync")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 added, 0 removed"));
+
+    let arrows_content = fs::read_to_string(output_dir.join("arrows.rs"))?;
+    assert!(arrows_content.contains("pub const Left: IconData"));
+    assert!(arrows_content.contains("width: \"32\""));
+    assert!(output_dir.join("test_icons.rs").exists());
+
+    // Drop the single file from the manifest
+    fs::write(
+        &manifest_path,
+        format!("icons = [{:?}]\n", arrows_dir.display().to_string()),
+    )?;
+
+    cmd()
+        .arg("sync")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 added, 1 removed"));
+
+    assert!(
+        !output_dir.join("test_icons.rs").exists(),
+        "test_icons.rs should be removed"
+    );
+    let mod_content = fs::read_to_string(output_dir.join("mod.rs"))?;
+    assert!(mod_content.contains("pub mod arrows;"));
+    assert!(!mod_content.contains("mod test_icons;"));
+
+    Ok(())
 }
 
-#[component]
-fn App() -> Element {
-    rsx! {
-        div {
-            Icon { data: mdi::Home }
-            Icon { data: heroicons::ArrowLeft }
-            Icon { data: lucide::Settings, width: "32", height: "32" }
-            Icon { data: mdi::Home, size: "24" }
-            Icon { data: heroicons::ArrowLeft, size: 32.to_string() }
-            Icon { data: lucide::Settings, size: "2em" }
-        }
-    }
+#[test]
+fn test_cli_sync_keeps_icons_of_broken_files() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let manifest_path = temp_dir.path().join("icons.toml");
+    let svg_dir = temp_dir.path().join("my-icons");
+    fs::create_dir(&svg_dir)?;
+    fs::write(
+        svg_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="4"/></svg>"#,
+    )?;
+    fs::write(
+        svg_dir.join("logo.svg"),
+        r#"<svg viewBox="0 0 24 24"><rect width="10" height="10"/></svg>"#,
+    )?;
+    fs::write(
+        &manifest_path,
+        "icons = [\"my-icons/dot.svg\", \"my-icons/\"]\n",
+    )?;
+
+    let sync = || {
+        cmd()
+            .arg("sync")
+            .arg("--manifest")
+            .arg(&manifest_path)
+            .arg("--output")
+            .arg(&output_dir)
+            .assert()
+    };
+    sync().success();
+
+    // Corrupted files, listed directly or through their directory
+    fs::write(svg_dir.join("dot.svg"), "<svg><circle")?;
+    sync().failure().stderr(predicate::str::contains("dot.svg"));
+    fs::write(
+        svg_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="4"/></svg>"#,
+    )?;
+    fs::write(svg_dir.join("logo.svg"), "not an svg")?;
+    sync()
+        .failure()
+        .stderr(predicate::str::contains("logo.svg"));
+
+    let content = fs::read_to_string(output_dir.join("my_icons.rs"))?;
+    assert!(content.contains("pub const Dot: IconData"));
+    assert!(content.contains("pub const Logo: IconData"));
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains("my-icons:dot"));
+    assert!(lockfile.contains("my-icons:logo"));
+
+    Ok(())
 }
-"#;
-    fs::write(project_dir.join("src/main.rs"), main_rs)?;
 
-    // Run cargo build and check it succeeds without warnings
-    let output = std::process::Command::new("cargo")
-        .args(["build", "--quiet"])
-        .current_dir(project_dir)
-        .output()?;
+#[test]
+fn test_cli_check() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        eprintln!("Build failed!");
-        eprintln!("STDOUT:\n{}", stdout);
-        eprintln!("STDERR:\n{}", stderr);
-        panic!("Build failed");
-    }
+    let test_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons/arrows");
 
-    // Check for warnings
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let full_output = format!("{}\n{}", stdout, stderr);
+    cmd()
+        .arg("add")
+        .arg(&test_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    cmd()
+        .arg("check")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("up to date"));
+
+    // Hand-edit the generated file
+    let arrows_file = output_dir.join("arrows.rs");
+    let content = fs::read_to_string(&arrows_file)?;
+    fs::write(&arrows_file, content + "\npub const Extra: u32 = 1;\n")?;
+
+    cmd()
+        .arg("check")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✗ arrows.rs"))
+        .stderr(predicate::str::contains("out of date"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_update_reimports_local_svgs() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let svg_dir = temp_dir.path().join("my-icons");
+    fs::create_dir(&svg_dir)?;
+    fs::write(
+        svg_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="4"/></svg>"#,
+    )?;
+    fs::write(
+        svg_dir.join("square.svg"),
+        r#"<svg viewBox="0 0 24 24"><rect width="10" height="10"/></svg>"#,
+    )?;
+
+    cmd()
+        .arg("add")
+        .arg(&svg_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    // Change one source file and delete the other
+    fs::write(
+        svg_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="8"/></svg>"#,
+    )?;
+    fs::remove_file(svg_dir.join("square.svg"))?;
 
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Re-importing 2 local SVG file(s)"))
+        .stdout(predicate::str::contains("0 removed upstream"))
+        .stdout(predicate::str::contains("1 missing local source(s)"))
+        .stdout(predicate::str::contains("my-icons:square ("))
+        .stdout(predicate::str::contains("square.svg)"))
+        .stdout(predicate::str::contains("Fetching latest versions").not())
+        .stderr(predicate::str::contains("file not found"));
+
+    let content = fs::read_to_string(output_dir.join("my_icons.rs"))?;
+    assert!(content.contains(r#"r="8""#), "dot should be re-imported");
     assert!(
-        !full_output.contains("warning:"),
-        "Generated code should not produce warnings"
+        content.contains("pub const Square: IconData"),
+        "square should be kept"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_update_from_another_directory() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let svg_dir = temp_dir.path().join("my-icons");
+    fs::create_dir(&svg_dir)?;
+    fs::write(
+        svg_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="4"/></svg>"#,
+    )?;
+
+    // Paths typed relative to the project root
+    cmd()
+        .current_dir(temp_dir.path())
+        .arg("add")
+        .arg("my-icons/dot.svg")
+        .arg("--output")
+        .arg("src/icons")
+        .assert()
+        .success();
+
+    let lockfile = fs::read_to_string(temp_dir.path().join("src/icons/icons.lock"))?;
+    assert!(
+        lockfile.contains(r#"path = "../../my-icons/dot.svg""#),
+        "paths should be relative to the icons directory: {}",
+        lockfile
+    );
+
+    // Updated from another working directory
+    cmd()
+        .current_dir(temp_dir.path().join("src"))
+        .arg("update")
+        .arg("--output")
+        .arg("icons")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 unchanged"))
+        .stderr(predicate::str::contains("not found").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_selective_update_with_report() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let shapes_dir = temp_dir.path().join("shapes");
+    let logos_dir = temp_dir.path().join("logos");
+    fs::create_dir(&shapes_dir)?;
+    fs::create_dir(&logos_dir)?;
+    fs::write(
+        shapes_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="4"/></svg>"#,
+    )?;
+    fs::write(
+        shapes_dir.join("square.svg"),
+        r#"<svg viewBox="0 0 24 24"><rect width="10" height="10"/></svg>"#,
+    )?;
+    fs::write(
+        logos_dir.join("brand.svg"),
+        r#"<svg viewBox="0 0 24 24"><path d="M0 0h24v24H0z"/></svg>"#,
+    )?;
+
+    cmd()
+        .arg("add")
+        .arg(&shapes_dir)
+        .arg(&logos_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    // Change icons in both collections
+    fs::write(
+        shapes_dir.join("dot.svg"),
+        r#"<svg viewBox="0 0 32 32"><circle cx="12" cy="12" r="8"/></svg>"#,
+    )?;
+    fs::write(
+        logos_dir.join("brand.svg"),
+        r#"<svg viewBox="0 0 24 24"><path d="M1 1h22v22H1z"/></svg>"#,
+    )?;
+    let logos_before = fs::read_to_string(output_dir.join("logos.rs"))?;
+
+    // Only update the shapes collection
+    cmd()
+        .arg("update")
+        .arg("shapes")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 unchanged"))
+        .stdout(predicate::str::contains(
+            "shapes:dot (body, viewBox, dimensions)",
+        ))
+        .stdout(predicate::str::contains("logos:brand").not());
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("logos.rs"))?,
+        logos_before,
+        "logos.rs should not be rewritten"
     );
 
+    // Update a single icon
+    cmd()
+        .arg("update")
+        .arg("logos:brand")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 unchanged"))
+        .stdout(predicate::str::contains("logos:brand (body)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_with_api_url_failover() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+
+    let down_url = serve_api(|_| (502, "bad gateway".to_string()));
+    let mirror_url = serve_api(|target| {
+        match target {
+        t if t.starts_with("/mdi.json?icons=home") => (
+            200,
+            r#"{"prefix":"mdi","width":24,"height":24,"icons":{"home":{"body":"<path d=\"M10 20v-6h4v6\"/>"}}}"#
+                .to_string(),
+        ),
+        t if t.starts_with("/collection?prefix=mdi") => (
+            200,
+            r#"{"prefix":"mdi","total":1,"info":{"name":"Material Design Icons","total":1}}"#
+                .to_string(),
+        ),
+        _ => (404, "404".to_string()),
+    }
+    });
+
+    cmd()
+        .arg("add")
+        .arg("mdi:home")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .env(
+            "DIOXUS_ICONIFY_API_URL",
+            format!("{},{}", down_url, mirror_url),
+        )
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "{} unavailable (status 502 Bad Gateway), trying {}",
+            down_url, mirror_url
+        )))
+        .stdout(predicate::str::contains(format!(
+            "mdi (1 icon(s)) ✓ via {}",
+            mirror_url
+        )));
+
+    // Icons and collection metadata come from the mirror
+    let content = fs::read_to_string(output_dir.join("mdi.rs"))?;
+    assert!(content.contains("pub const Home"));
+    assert!(content.contains("Material Design Icons"));
+
+    // --api-url takes precedence over the environment and the manifest
+    let manifest_path = temp_dir.path().join("icons.toml");
+    fs::write(
+        &manifest_path,
+        format!("api_urls = [\"{}\", \"{}\"]\n", down_url, mirror_url),
+    )?;
+
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .arg("--api-url")
+        .arg(&mirror_url)
+        .env("DIOXUS_ICONIFY_API_URL", &down_url)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "mdi (1 icon(s)) ✓ via {}",
+            mirror_url
+        )))
+        .stderr(predicate::str::contains("unavailable").not());
+
+    // The manifest api_urls are used when no URL is given on the command line
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .env_remove("DIOXUS_ICONIFY_API_URL")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "{} unavailable",
+            down_url
+        )))
+        .stdout(predicate::str::contains(format!(
+            "mdi (1 icon(s)) ✓ via {}",
+            mirror_url
+        )));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_with_api_down() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let api_url = serve_api(|_| (503, "maintenance".to_string()));
+
+    cmd()
+        .arg("add")
+        .arg("mdi:home")
+        .arg("--output")
+        .arg(temp_dir.path().join("icons"))
+        .arg("--api-url")
+        .arg(&api_url)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--retries")
+        .arg("1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("retrying in"))
+        .stderr(predicate::str::contains("after 2 attempt(s)"))
+        .stderr(predicate::str::contains("The Iconify API is unreachable"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_from_local_iconify_json() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let package_dir = temp_dir.path().join("node_modules/@iconify/json/json");
+    fs::create_dir_all(&package_dir)?;
+    fs::write(
+        package_dir.join("test.json"),
+        r#"{
+            "prefix": "test",
+            "info": { "name": "Test Icons", "total": 2 },
+            "width": 24,
+            "height": 24,
+            "icons": {
+                "home": { "body": "<path d=\"M1 1\"/>", "left": 2, "top": 2, "width": 20, "height": 20 }
+            },
+            "aliases": {
+                "house": { "parent": "home" }
+            }
+        }"#,
+    )?;
+
+    // Found automatically in node_modules, the (unreachable) API is never used
+    cmd()
+        .current_dir(temp_dir.path())
+        .arg("add")
+        .arg("test:home")
+        .arg("test:house")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--api-url")
+        .arg("http://127.0.0.1:1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "test (2 icon(s)) ✓ via node_modules",
+        ));
+
+    let content = fs::read_to_string(output_dir.join("test.rs"))?;
+    assert!(content.contains("pub const House"));
+    assert!(content.contains(r#"view_box: "2 2 20 20""#));
+    assert!(content.contains("Test Icons"));
+
+    // Or from an explicit directory
+    let other_dir = TempDir::new()?;
+    cmd()
+        .current_dir(other_dir.path())
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--source")
+        .arg(temp_dir.path().join("node_modules/@iconify/json"))
+        .arg("--api-url")
+        .arg("http://127.0.0.1:1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 unchanged"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_offline_from_cache() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let cache_dir = temp_dir.path().join("cache");
+
+    let api_url = serve_api(|target| match target {
+        t if t.starts_with("/mdi.json?icons=home") => (
+            200,
+            r#"{"prefix":"mdi","width":24,"height":24,"icons":{"home":{"body":"<path/>"}}}"#
+                .to_string(),
+        ),
+        _ => (404, "404".to_string()),
+    });
+
+    cmd()
+        .arg("add")
+        .arg("mdi:home")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--api-url")
+        .arg(&api_url)
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .assert()
+        .success();
+
+    cmd()
+        .arg("cache")
+        .arg("info")
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 response(s)"));
+
+    // Same request served from the cache
+    fs::remove_dir_all(&output_dir)?;
+    cmd()
+        .arg("add")
+        .arg("mdi:home")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--api-url")
+        .arg(&api_url)
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .arg("--offline")
+        .assert()
+        .success();
+    assert!(output_dir.join("mdi.rs").exists());
+
+    // Other requests fail
+    cmd()
+        .arg("add")
+        .arg("mdi:account")
+        .arg("--output")
+        .arg(&output_dir)
+        .arg("--api-url")
+        .arg(&api_url)
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .arg("--offline")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not in the cache (offline mode)"));
+
+    cmd()
+        .arg("cache")
+        .arg("clear")
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 cached response(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_svg_with_offset_and_fractional_size() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let manifest_path = temp_dir.path().join("icons.toml");
+
+    let offset_svg =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons/offset.svg");
+
+    cmd()
+        .arg("add")
+        .arg(&offset_svg)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(output_dir.join("test_icons.rs"))?;
+    assert!(content.contains(r#"view_box: "0.5 -1.25 20.5 21""#));
+    assert!(content.contains(r#"width: "20.5""#));
+    assert!(content.contains(r#"height: "21""#));
+
+    // A fractional size override keeps the original viewBox
+    fs::write(
+        &manifest_path,
+        format!(
+            "icons = [{:?}]\n\n[options.\"test-icons:offset\"]\nwidth = 10.25\n",
+            offset_svg.display().to_string()
+        ),
+    )?;
+
+    cmd()
+        .arg("sync")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(output_dir.join("test_icons.rs"))?;
+    assert!(content.contains(r#"view_box: "0.5 -1.25 20.5 21""#));
+    assert!(content.contains(r#"width: "10.25""#));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_with_source_schemes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let sets_dir = temp_dir.path().join("sets");
+    fs::create_dir_all(&sets_dir)?;
+    fs::write(
+        sets_dir.join("test.json"),
+        r#"{ "prefix": "test", "icons": { "home": { "body": "<path/>" } } }"#,
+    )?;
+
+    let simple_svg =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons/simple.svg");
+
+    cmd()
+        .arg("add")
+        .arg(format!("file:{}", simple_svg.display()))
+        .arg("json:test:home")
+        .arg("--source")
+        .arg(&sets_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test-icons:simple ✓"))
+        .stdout(predicate::str::contains("test (1 icon(s)) ✓ via"));
+
+    assert!(output_dir.join("test_icons.rs").exists());
+    assert!(output_dir.join("test.rs").exists());
+
+    // `json:` never falls back to the API
+    cmd()
+        .arg("add")
+        .arg("json:mdi:home")
+        .arg("--source")
+        .arg(&sets_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No IconifyJSON file for mdi"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_sprite_sheet() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let sprite = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sprites/sprite.svg");
+
+    cmd()
+        .arg("add")
+        .arg(&sprite)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 3 symbol(s)"))
+        .stdout(predicate::str::contains("sprites:dots ✓"));
+
+    let content = fs::read_to_string(output_dir.join("sprites.rs"))?;
+    assert!(content.contains("pub const Home: IconData"));
+    assert!(content.contains("pub const Dot: IconData"));
+    assert!(content.contains("pub const Dots: IconData"));
+    assert!(content.contains(r#"view_box: "-1 -1 34 18""#));
+
+    // IDs are prefixed per icon, with their references
+    assert!(content.contains(r#"<linearGradient id="sprites_home_fade">"#));
+    assert!(content.contains(r##"fill="url(#sprites_home_fade-vertical)""##));
+    assert!(content.contains(r##"<use xlink:href="#sprites_dots_dot" x="16"/>"##));
+    let mod_rs = fs::read_to_string(output_dir.join("mod.rs"))?;
+    assert!(mod_rs.contains("unique_ids: bool"));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"symbol = "dots""#));
+
+    // Each symbol is re-imported from the sprite sheet
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 unchanged"));
+
+    Ok(())
+}
+
+/// Write an npm-like tarball (`package/...` entries)
+fn write_tgz(path: &Path, files: &[(&str, &str)]) -> Result<()> {
+    let encoder = flate2::write::GzEncoder::new(fs::File::create(path)?, Default::default());
+    let mut tar = tar::Builder::new(encoder);
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, format!("package/{}", name), content.as_bytes())?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_from_archive() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let tgz = temp_dir.path().join("my-icons-1.2.0.tgz");
+
+    let square = r#"<svg viewBox="0 0 24 24"><rect width="20" height="20"/></svg>"#;
+    write_tgz(
+        &tgz,
+        &[
+            ("package.json", "{}"),
+            ("icons/square.svg", square),
+            (
+                "icons/arrows/left.svg",
+                r#"<svg viewBox="0 0 24 24"><path d="M1 1"/></svg>"#,
+            ),
+            ("README.svg", r#"<svg viewBox="0 0 24 24"/>"#),
+        ],
+    )?;
+
+    cmd()
+        .arg("add")
+        .arg(&tgz)
+        .arg("--path")
+        .arg("icons/")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 2 SVG(s)"));
+
+    // The collection defaults to the archive name without version
+    let content = fs::read_to_string(output_dir.join("my_icons.rs"))?;
+    assert!(content.contains("pub const Square: IconData"));
+    assert!(content.contains("pub const ArrowsLeft: IconData"));
+    assert!(!content.contains("Readme"));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"origin = "archive""#));
+    assert!(lockfile.contains(r#"entry = "package/icons/square.svg""#));
+    assert!(lockfile.contains("archive_hash = \"sha256:"));
+
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 unchanged"));
+
+    // A new version of the archive: the changed icon and the new archive hash are recorded
+    write_tgz(
+        &tgz,
+        &[
+            ("icons/square.svg", &square.replace("20", "22")),
+            (
+                "icons/arrows/left.svg",
+                r#"<svg viewBox="0 0 24 24"><path d="M1 1"/></svg>"#,
+            ),
+        ],
+    )?;
+
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("my-icons:square (body, archive)"))
+        .stdout(predicate::str::contains("my-icons:arrows-left (archive)"));
+
+    // Or under a chosen collection
+    cmd()
+        .arg("add")
+        .arg(format!("archive:{}", tgz.display()))
+        .arg("--path")
+        .arg("icons/arrows")
+        .arg("--collection")
+        .arg("brand")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(output_dir.join("brand.rs"))?;
+    assert!(content.contains("pub const Left: IconData"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_custom_icon_set_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let icon_set = temp_dir.path().join("company-icons.json");
+    let write_icon_set = |logo: &str| {
+        fs::write(
+            &icon_set,
+            format!(
+                r#"{{
+                    "prefix": "company",
+                    "info": {{ "name": "Company Icons", "author": "Design Team", "license": "Proprietary" }},
+                    "width": 32,
+                    "height": 32,
+                    "icons": {{
+                        "logo": {{ "body": "{}" }},
+                        "badge": {{ "body": "<circle r=\"4\"/>", "width": 16, "height": 16 }}
+                    }},
+                    "aliases": {{ "logo-mirrored": {{ "parent": "logo", "hFlip": true }} }}
+                }}"#,
+                logo
+            ),
+        )
+    };
+    write_icon_set(r#"<path d=\"M0 0h32v32z\"/>"#)?;
+
+    // Selected icons
+    cmd()
+        .arg("add")
+        .arg(format!("{}:logo", icon_set.display()))
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("company (1 icon(s)) ✓ via"))
+        .stdout(predicate::str::contains("Fetching info for company... ✓"));
+
+    let content = fs::read_to_string(output_dir.join("company.rs"))?;
+    assert!(content.contains("/// name: Company Icons"));
+    assert!(content.contains("/// author: Design Team"));
+    assert!(content.contains("pub const Logo: IconData"));
+    assert!(content.contains(r#"view_box: "0 0 32 32""#));
+    assert!(!content.contains("pub const Badge: IconData"));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"origin = "iconset""#));
+
+    // All icons and aliases
+    cmd()
+        .arg("add")
+        .arg(&icon_set)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("company (3 icon(s)) ✓ via"));
+
+    let content = fs::read_to_string(output_dir.join("company.rs"))?;
+    assert!(content.contains("pub const Badge: IconData"));
+    assert!(content.contains(r#"view_box: "0 0 16 16""#));
+    assert!(content.contains("pub const LogoMirrored: IconData"));
+
+    // Icons are re-read from the file
+    write_icon_set(r#"<path d=\"M0 0h16v16z\"/>"#)?;
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 unchanged"))
+        .stdout(predicate::str::contains("company:logo (body)"))
+        .stdout(predicate::str::contains("company:logo-mirrored (body)"));
+
+    // Unknown icons of the file are reported
+    cmd()
+        .arg("add")
+        .arg(format!("{}:ghost", icon_set.display()))
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("company:ghost"));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_export_iconify_json() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let export_dir = temp_dir.path().join("exported");
+    let test_icons = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-icons");
+
+    cmd()
+        .arg("add")
+        .arg(&test_icons)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    cmd()
+        .arg("export")
+        .arg("--format")
+        .arg("iconify-json")
+        .arg("--out-dir")
+        .arg(&export_dir)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test-icons.json (6 icon(s))"));
+
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(export_dir.join("test-icons.json"))?)?;
+    assert_eq!(exported["prefix"], "test-icons");
+    assert_eq!(exported["icons"]["offset"]["left"], 0.5);
+    assert_eq!(exported["icons"]["offset"]["width"], 20.5);
+
+    // The exported set is imported back identically
+    let reimported_dir = temp_dir.path().join("reimported");
+    cmd()
+        .arg("add")
+        .arg(export_dir.join("test-icons.json"))
+        .arg("--output")
+        .arg(&reimported_dir)
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("test_icons.rs"))?,
+        fs::read_to_string(reimported_dir.join("test_icons.rs"))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_add_with_optimize() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let editors = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/editors");
+
+    cmd()
+        .arg("add")
+        .arg(&editors)
+        .arg("--optimize")
+        .arg("safe")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("🧹 Optimized 2 SVG(s):"))
+        .stdout(predicate::str::contains("editors:illustrator (safe): "));
+
+    let content = fs::read_to_string(output_dir.join("editors.rs"))?;
+    assert!(content.contains(r#"d="M10,20v-6h4v6h5v-8h3L12,3L2,12h3v8H10z""#));
+    assert!(!content.contains("<title>"));
+    assert!(!content.contains("nodetypes"));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"optimize = "safe""#));
+
+    // The recorded preset is re-applied
+    cmd()
+        .arg("update")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 unchanged"));
+
+    // Another preset can be given
+    cmd()
+        .arg("update")
+        .arg("--optimize")
+        .arg("aggressive")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "editors:inkscape (body, optimize)",
+        ))
+        .stdout(predicate::str::contains(
+            "editors:illustrator (body, optimize)",
+        ));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"optimize = "aggressive""#));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_sync_with_monochrome() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+    let manifest_path = temp_dir.path().join("icons.toml");
+    let colors = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/colors");
+
+    fs::write(
+        &manifest_path,
+        format!(
+            "icons = [{:?}]\n\n[options.colors]\nmonochrome = \"auto\"\n",
+            colors.display().to_string()
+        ),
+    )?;
+
+    cmd()
+        .arg("sync")
+        .arg("--manifest")
+        .arg(&manifest_path)
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "🎨 Converted 1 icon(s) to currentColor:",
+        ))
+        .stdout(predicate::str::contains("colors:chart (#1e293b)"))
+        .stderr(predicate::str::contains(
+            "colors:flag has 3 colours (#0055a4, #ef4135, #ffffff), kept as-is",
+        ));
+
+    let content = fs::read_to_string(output_dir.join("colors.rs"))?;
+    assert!(content.contains(r#"<g fill="none" stroke="currentColor""#));
+    assert!(content.contains(r#"style="stroke: currentColor""#));
+    assert!(content.contains(r##"fill="#EF4135""##));
+
+    let lockfile = fs::read_to_string(output_dir.join("icons.lock"))?;
+    assert!(lockfile.contains(r#"monochrome = "auto""#));
+
+    // Multi-colour icons can be converted anyway
+    cmd()
+        .arg("update")
+        .arg("colors:flag")
+        .arg("--monochrome")
+        .arg("force")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("colors:flag (body, monochrome)"));
+
+    let content = fs::read_to_string(output_dir.join("colors.rs"))?;
+    assert!(!content.contains(r##"fill="#EF4135""##));
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_import_options_only_for_import_commands() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("icons");
+
+    cmd()
+        .arg("list")
+        .arg("--optimize")
+        .arg("safe")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--optimize"));
+
+    // Network and cache options are global
+    cmd()
+        .arg("--offline")
+        .arg("list")
+        .arg("--timeout")
+        .arg("5")
+        .arg("--output")
+        .arg(&output_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+#[ignore] // Requires internet connection and takes time to compile
+fn test_generated_code_compiles() -> Result<()> {
+    // Create a temporary directory for our test project
+    let temp_dir = TempDir::new()?;
+    let project_dir = temp_dir.path();
+    let icons_dir = project_dir.join("src/icons");
+
+    // Add some test icons using the CLI
+    cmd()
+        .arg("add")
+        .arg("mdi:home")
+        .arg("heroicons:arrow-left")
+        .arg("lucide:settings")
+        .arg("--output")
+        .arg(&icons_dir)
+        .assert()
+        .success();
+
+    // Create a minimal Cargo.toml for the test project
+    let cargo_toml = r#"[package]
+name = "icon-test"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+dioxus = "0.7"
+"#;
+    fs::write(project_dir.join("Cargo.toml"), cargo_toml)?;
+
+    // Create a main.rs that uses the generated icons
+    let main_rs = r#"#![deny(warnings)]
+
+mod icons;
+
+use dioxus::prelude::*;
+use icons::Icon;
+use icons::{heroicons, lucide, mdi};
+
+fn main() {
+    // Use the icons to avoid dead_code warnings
+    let _home = mdi::Home;
+    let _arrow = heroicons::ArrowLeft;
+    let _settings = lucide::Settings;
+
+    println!("Icons loaded successfully");
+}
+
+#[component]
+fn App() -> Element {
+    rsx! {
+        div {
+            Icon { data: mdi::Home }
+            Icon { data: heroicons::ArrowLeft }
+            Icon { data: lucide::Settings, width: "32", height: "32" }
+            Icon { data: mdi::Home, size: "24" }
+            Icon { data: heroicons::ArrowLeft, size: 32.to_string() }
+            Icon { data: lucide::Settings, size: "2em" }
+        }
+    }
+}
+"#;
+    fs::write(project_dir.join("src/main.rs"), main_rs)?;
+
+    // Run cargo build and check it succeeds without warnings
+    let output = std::process::Command::new("cargo")
+        .args(["build", "--quiet"])
+        .current_dir(project_dir)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        eprintln!("Build failed!");
+        eprintln!("STDOUT:\n{}", stdout);
+        eprintln!("STDERR:\n{}", stderr);
+        panic!("Build failed");
+    }
+
+    // Check for warnings
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let full_output = format!("{}\n{}", stdout, stderr);
+
+    assert!(
+        !full_output.contains("warning:"),
+        "Generated code should not produce warnings"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[ignore] // Takes time to compile
+fn test_unique_ids_render_per_instance() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project_dir = temp_dir.path();
+    let svg_dir = project_dir.join("my-icons");
+    fs::create_dir(&svg_dir)?;
+    fs::write(
+        svg_dir.join("logo.svg"),
+        r##"<svg viewBox="0 0 24 24"><title id="t">Logo</title><desc>my-icons_logo_a</desc><defs><linearGradient id="a"><stop offset="0" stop-color="red"/></linearGradient></defs><g aria-labelledby="t"><circle fill="url(#a)" r="10"/><use href="#a"/></g></svg>"##,
+    )?;
+
+    cmd()
+        .arg("add")
+        .arg(&svg_dir)
+        .arg("--output")
+        .arg(project_dir.join("src/icons"))
+        .assert()
+        .success();
+
+    let cargo_toml = r#"[package]
+name = "unique-ids-test"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+dioxus = { version = "0.7", default-features = false, features = ["macro", "html", "signals", "hooks"] }
+"#;
+    fs::write(project_dir.join("Cargo.toml"), cargo_toml)?;
+
+    // Render two instances of the icon, and print their bodies
+    let main_rs = r#"#![deny(warnings)]
+
+mod icons;
+
+use dioxus::core::{AttributeValue, Mutation};
+use dioxus::prelude::*;
+use icons::{my_icons, Icon};
+
+fn main() {
+    let mut dom = VirtualDom::new(App);
+    for edit in dom.rebuild_to_vec().edits {
+        if let Mutation::SetAttribute {
+            name: "dangerous_inner_html",
+            value: AttributeValue::Text(body),
+            ..
+        } = edit
+        {
+            println!("{}", body);
+        }
+    }
+}
+
+#[component]
+fn App() -> Element {
+    rsx! {
+        Icon { data: my_icons::Logo, unique_ids: true }
+        Icon { data: my_icons::Logo, unique_ids: true }
+    }
+}
+"#;
+    fs::write(project_dir.join("src/main.rs"), main_rs)?;
+
+    let output = std::process::Command::new("cargo")
+        .args(["run", "--quiet"])
+        .current_dir(project_dir)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "Run failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let bodies: Vec<&str> = stdout.lines().collect();
+    assert_eq!(bodies.len(), 2, "Expected two rendered bodies: {}", stdout);
+    assert_ne!(bodies[0], bodies[1], "Instances should have distinct IDs");
+
+    for body in bodies {
+        let scope = body
+            .split("id=\"my-icons_logo_")
+            .nth(1)
+            .and_then(|rest| rest.split('_').next())
+            .expect("the scope should be inserted after the prefix");
+        let prefix = format!("my-icons_logo_{}_", scope);
+        assert!(body.contains(&format!(r#"<title id="{}t">"#, prefix)));
+        assert!(body.contains(&format!(r#"<linearGradient id="{}a">"#, prefix)));
+        assert!(body.contains(&format!(r#"aria-labelledby="{}t""#, prefix)));
+        assert!(body.contains(&format!(r#"fill="url(#{}a)""#, prefix)));
+        assert!(body.contains(&format!(r##"href="#{}a""##, prefix)));
+        // Text that looks like an ID is left as-is
+        assert!(body.contains("<desc>my-icons_logo_a</desc>"));
+    }
+
     Ok(())
 }
 